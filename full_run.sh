@@ -1,4 +1,2 @@
 rm logs/*
-rm CSVs/data_filtered_citations_changed.csv
-python preprocess_citations.py
-./target/release/cartesian-law-analysis
\ No newline at end of file
+./target/release/cartesian-law-analysis
@@ -0,0 +1,449 @@
+// Article numbers and ranges cited by a citation
+//
+// "Art. 12", "Art. 1-5" and "Art. 41 ff." are kept as ranges until Phase 2,
+// where they are expanded according to the configured `RangeRules`.
+use crate::{markers, AHashSet};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+
+// Article number: Arabic with an optional ordinal ("1er", "1re", "1°") or Roman ("Art. IV")
+const ARTICLE_NUMBER: &str = r"(\d+|[IVXLC]+\b)";
+
+const LATIN_SUFFIX: &str = r"bis|ter|quater|quinquies|sexies|septies|octies|novies|decies";
+
+const ORDINAL: &str = r"(?i:er|re|°)?";
+
+static ART_SS_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"{}\s*{}{}\s*(?i:et?\s+)?{}", *markers::ARTICLE, ARTICLE_NUMBER, ORDINAL, *markers::FOLLOWING)).unwrap()
+});
+
+// "Art. 1-5", "art. 1 à 5", "Art. 1 bis 5"; "bis" only with spaces on both sides,
+// so "Art. 6bis 8" stays article 6bis (see ARTICLE_ID_PATTERN)
+static ART_RANGE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"{}\s*{}{}(?:\s*(?:-|à)\s*|\s+(?i:bis)\s+){}",
+        *markers::ARTICLE, ARTICLE_NUMBER, ORDINAL, ARTICLE_NUMBER
+    )).unwrap()
+});
+
+static ART_SIMPLE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"{}\s*{}", *markers::ARTICLE, ARTICLE_NUMBER)).unwrap()
+});
+
+// Further articles enumerated after the first one or a range: "Art. 8 und 9",
+// "art. 12, 13 et 15", "Art. 1-5 and 9"; group 1 is the enumeration
+static ART_LIST_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"{marker}\s*\d+{ord}(?:\s?(?:{latin})\b|[a-z]\b)?(?:\s*-\s*\d+)?((?:(?:\s*,\s*|\s+(?:und|et|e|and)\s+)\d+{ord}(?:\s?(?:{latin})\b|[a-z]\b)?)+)",
+        marker = *markers::ARTICLE, ord = ORDINAL, latin = LATIN_SUFFIX
+    )).unwrap()
+});
+
+static LIST_ITEM_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"(\d+)(?:{})?(?:\s?({})\b|([a-z])\b)?", ORDINAL, LATIN_SUFFIX)).unwrap()
+});
+
+static TRANSITIONAL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)dispositions?\s+transitoires?|disposizioni\s+transitorie|disp\.\s*trans\b|übergangsbestimmung|übergangsbest\.|(?:ü|ue)b\.?\s*best\.").unwrap()
+});
+
+// Final title of the Civil Code ("SchlT ZGB", "Tit. fin. CC") and final provisions of an act
+static FINAL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bschlt\b|schlusstitel|titre\s+final|tit\.\s*fin\b|titolo\s+finale|dispositions?\s+finales|disposizioni\s+finali|disp\.\s*fin\b|schlussbestimmung|schlussbest\.").unwrap()
+});
+
+// "Art. 40a", "Art. 6bis", "art. 6 bis"; "Art. 1 bis 5" is a range as in
+// ART_RANGE_PATTERN, "Art. 12f." a page-style "f."
+static ARTICLE_ID_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"{}\s*(\d+)(?:\s?({})\b|([a-z])\b)?", *markers::ARTICLE, LATIN_SUFFIX)).unwrap()
+});
+
+/// Part of the act an article belongs to; "Art. 1 SchlT ZGB" is not Art. 1 ZGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ProvisionPart {
+    #[default]
+    Main,
+    /// "disp. trans.", "Übergangsbestimmungen"
+    Transitional,
+    /// "SchlT ZGB", "Tit. fin. CC", "Schlussbestimmungen"
+    Final,
+}
+
+impl ProvisionPart {
+    fn detect(citation: &str) -> Self {
+        if FINAL_PATTERN.is_match(citation) {
+            ProvisionPart::Final
+        } else if TRANSITIONAL_PATTERN.is_match(citation) {
+            ProvisionPart::Transitional
+        } else {
+            ProvisionPart::Main
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            ProvisionPart::Main => "",
+            ProvisionPart::Transitional => "trans. ",
+            ProvisionPart::Final => "final ",
+        }
+    }
+}
+
+/// A single article ("Art. 12" is 12..=12), a closed range ("Art. 1-5") or an
+/// open range ("Art. 41 ff.", `end` is `None`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ArticleRange {
+    pub part: ProvisionPart,
+    /// "Art. IV": Roman-numbered articles are distinct from Arabic ones
+    pub roman: bool,
+    pub start: u32,
+    pub end: Option<u32>,
+}
+
+impl ArticleRange {
+    pub fn single(article: u32) -> Self {
+        ArticleRange { part: ProvisionPart::Main, roman: false, start: article, end: Some(article) }
+    }
+
+    /// Main-body article with Arabic numbering, the only kind a law structure
+    /// or concordance table describes
+    pub fn is_plain(&self) -> bool {
+        self.part == ProvisionPart::Main && !self.roman
+    }
+
+    fn provision(&self, number: u32) -> Provision {
+        Provision { part: self.part, roman: self.roman, number }
+    }
+}
+
+/// One article after range expansion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Provision {
+    pub part: ProvisionPart,
+    pub roman: bool,
+    pub number: u32,
+}
+
+fn format_number(number: u32, roman: bool) -> String {
+    if roman { to_roman(number) } else { number.to_string() }
+}
+
+impl fmt::Display for ArticleRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let start = format_number(self.start, self.roman);
+        match self.end {
+            None => write!(f, "{}{} ff.", self.part.prefix(), start),
+            Some(end) if end == self.start => write!(f, "{}{}", self.part.prefix(), start),
+            Some(end) => write!(f, "{}{}-{}", self.part.prefix(), start, format_number(end, self.roman)),
+        }
+    }
+}
+
+impl fmt::Display for Provision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.part.prefix(), format_number(self.number, self.roman))
+    }
+}
+
+// Main-body articles stay plain numbers in the match output; "final 1", "IV"
+impl Serialize for Provision {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.part == ProvisionPart::Main && !self.roman {
+            serializer.serialize_u32(self.number)
+        } else {
+            serializer.collect_str(self)
+        }
+    }
+}
+
+// "41", "1-5", "41 ff.", "final 1" in JSON outputs
+impl Serialize for ArticleRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// How far an open range like "Art. 41 ff." reaches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenRangeMode {
+    /// Only the first article
+    StartOnly,
+    /// The first article and the `open_range_window` following ones
+    Window,
+    /// Up to the last article of the law, at most `max_range_span` articles;
+    /// `Window` when the law structure is unknown
+    ToEndOfLaw,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RangeRules {
+    pub open_range_mode: OpenRangeMode,
+    pub open_range_window: u32,
+    /// Closed ranges spanning more articles are reduced to their two endpoints
+    pub max_range_span: u32,
+}
+
+impl Default for RangeRules {
+    fn default() -> Self {
+        RangeRules {
+            open_range_mode: OpenRangeMode::Window,
+            open_range_window: 10,
+            max_range_span: 200,
+        }
+    }
+}
+
+/// "IV" -> 4, up to C (100); `None` for non-canonical numerals like "IIII" or "VX"
+fn from_roman(numeral: &str) -> Option<u32> {
+    let values: Vec<u32> = numeral.chars()
+        .map(|c| match c {
+            'I' => Some(1),
+            'V' => Some(5),
+            'X' => Some(10),
+            'L' => Some(50),
+            'C' => Some(100),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    let mut total: i64 = 0;
+    for (i, &value) in values.iter().enumerate() {
+        if values.get(i + 1).is_some_and(|&next| next > value) {
+            total -= value as i64;
+        } else {
+            total += value as i64;
+        }
+    }
+    u32::try_from(total).ok().filter(|&n| n > 0 && to_roman(n) == numeral)
+}
+
+fn to_roman(mut number: u32) -> String {
+    const NUMERALS: [(u32, &str); 9] = [
+        (100, "C"), (90, "XC"), (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+    let mut roman = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            roman.push_str(numeral);
+            number -= value;
+        }
+    }
+    roman
+}
+
+/// (number, roman) of a captured article number
+fn parse_article_number(text: &str) -> Option<(u32, bool)> {
+    match text.parse::<u32>() {
+        Ok(number) => Some((number, false)),
+        Err(_) => from_roman(text).map(|number| (number, true)),
+    }
+}
+
+/// Part of the article whose marker starts at `start`, from the text up to the
+/// next article marker ("Art. 1 SchlT ZGB und Art. 8 ZGB": only Art. 1 is in
+/// the final title), else from the text before the first marker ("SchlT ZGB Art. 1")
+fn part_at(citation: &str, marker_starts: &[usize], start: usize) -> ProvisionPart {
+    let end = marker_starts.iter().copied().find(|&marker| marker > start).unwrap_or(citation.len());
+    match ProvisionPart::detect(&citation[start..end]) {
+        ProvisionPart::Main => ProvisionPart::detect(&citation[..marker_starts.first().copied().unwrap_or(0)]),
+        part => part,
+    }
+}
+
+/// Sorted, without singles already starting a range
+pub fn extract_article_numbers(citation: &str) -> Vec<ArticleRange> {
+    let marker_starts: Vec<usize> = ART_SIMPLE_PATTERN.find_iter(citation).map(|m| m.start()).collect();
+    let part_of = |caps: &regex::Captures| part_at(citation, &marker_starts, caps.get(0).unwrap().start());
+    let mut ranges = Vec::new();
+
+    for caps in ART_SS_PATTERN.captures_iter(citation) {
+        if let Some((start, roman)) = parse_article_number(&caps[1]) {
+            ranges.push(ArticleRange { part: part_of(&caps), roman, start, end: None });
+        }
+    }
+
+    for caps in ART_RANGE_PATTERN.captures_iter(citation) {
+        if let (Some((start, roman)), Some((end, end_roman))) =
+            (parse_article_number(&caps[1]), parse_article_number(&caps[2]))
+        {
+            if start <= end && roman == end_roman {
+                ranges.push(ArticleRange { part: part_of(&caps), roman, start, end: Some(end) });
+            }
+        }
+    }
+
+    for caps in ART_SIMPLE_PATTERN.captures_iter(citation) {
+        if let Some((num, roman)) = parse_article_number(&caps[1]) {
+            if !ranges.iter().any(|range| range.start == num && range.roman == roman) {
+                ranges.push(ArticleRange { part: part_of(&caps), roman, start: num, end: Some(num) });
+            }
+        }
+    }
+
+    for caps in ART_LIST_PATTERN.captures_iter(citation) {
+        let part = part_of(&caps);
+        for item in LIST_ITEM_PATTERN.captures_iter(&caps[1]) {
+            if let Ok(num) = item[1].parse::<u32>() {
+                if !ranges.iter().any(|range| range.start == num && !range.roman) {
+                    ranges.push(ArticleRange { part, roman: false, start: num, end: Some(num) });
+                }
+            }
+        }
+    }
+
+    ranges.sort_unstable();
+    ranges.dedup();
+    ranges
+}
+
+/// Article identifiers as written, suffix included: "8", "40a", "6bis"
+pub fn extract_article_ids(citation: &str) -> Vec<String> {
+    let enumerated = ART_LIST_PATTERN.captures_iter(citation)
+        .flat_map(|caps| LIST_ITEM_PATTERN.captures_iter(caps.get(1).unwrap().as_str()).collect::<Vec<_>>())
+        .map(|item| format!("{}{}", &item[1], item.get(2).or(item.get(3)).map_or("", |suffix| suffix.as_str())));
+    ARTICLE_ID_PATTERN.captures_iter(citation)
+        .map(|caps| {
+            let rest = &citation[caps.get(0).unwrap().end()..];
+            let suffix = match (caps.get(2), caps.get(3)) {
+                // "Art. 1 bis 5": range word, not a suffix
+                (Some(latin), _) if latin.as_str() == "bis"
+                    && latin.start() > caps.get(1).unwrap().end()
+                    && rest.starts_with(char::is_whitespace)
+                    && rest.trim_start().starts_with(|c: char| c.is_ascii_digit()) => "",
+                (Some(latin), _) => latin.as_str(),
+                (None, Some(_)) if rest.starts_with('.') => "",
+                (None, Some(letter)) => letter.as_str(),
+                (None, None) => "",
+            };
+            format!("{}{}", &caps[1], suffix)
+        })
+        .chain(enumerated)
+        .collect()
+}
+
+/// Provisions covered by `ranges` under `rules`; `max_article` is the highest
+/// article of the cited law, when the law structure knows it (main body only)
+pub fn expand_articles(ranges: &[ArticleRange], rules: &RangeRules, max_article: Option<u32>) -> AHashSet<Provision> {
+    let mut provisions = AHashSet::new();
+    for range in ranges {
+        let max_article = max_article.filter(|_| range.is_plain());
+        let clamp = |end: u32| match max_article {
+            Some(max) => end.min(max).max(range.start),
+            None => end,
+        };
+
+        match range.end {
+            Some(end) if end - range.start > rules.max_range_span => {
+                // "Art. 1-9999": keep what was actually written
+                provisions.insert(range.provision(range.start));
+                provisions.insert(range.provision(end));
+            }
+            Some(end) => provisions.extend((range.start..=clamp(end)).map(|n| range.provision(n))),
+            None => {
+                let window_end = range.start.saturating_add(rules.open_range_window);
+                let end = match (rules.open_range_mode, max_article) {
+                    (OpenRangeMode::StartOnly, _) => range.start,
+                    (OpenRangeMode::ToEndOfLaw, Some(max)) => {
+                        max.max(range.start).min(range.start.saturating_add(rules.max_range_span))
+                    }
+                    (OpenRangeMode::Window, _) | (OpenRangeMode::ToEndOfLaw, None) => clamp(window_end),
+                };
+                provisions.extend((range.start..=end).map(|n| range.provision(n)));
+            }
+        }
+    }
+    provisions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(citation: &str) -> Vec<String> {
+        extract_article_numbers(citation).iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn extracts_article_numbers() {
+        let cases: [(&str, &[&str]); 18] = [
+            ("Art. 12 ZGB", &["12"]),
+            ("Art. 1-5 OR", &["1-5"]),
+            ("art. 1 à 5 CO", &["1-5"]),
+            ("Art. 1 bis 5 ZGB", &["1-5"]),
+            ("Art. 41 ff. OR", &["41 ff."]),
+            ("art. 1er al. 2 CC", &["1"]),
+            ("Art. 8 und 9 BV", &["8", "9"]),
+            ("art. 12, 13 et 15 CC", &["12", "13", "15"]),
+            ("art. 5 e 6 LAVS", &["5", "6"]),
+            ("Art. 1-5 and 9 ZGB", &["1-5", "9"]),
+            ("Art. 8 Abs. 2 und 3 BV", &["8"]),
+            ("Art. XL BV", &["XL"]),
+            ("Art. IIII BV", &[]),
+            ("Art. 6bis 8 BV", &["6"]),
+            ("Art. II-IV BV", &["II-IV"]),
+            ("Art. IV disp. trans. Cst.", &["trans. IV"]),
+            ("SchlT ZGB Art. 1", &["final 1"]),
+            ("Art. 1 SchlT ZGB und Art. 8 ZGB", &["8", "final 1"]),
+        ];
+        for (citation, expected) in cases {
+            assert_eq!(numbers(citation), expected, "{}", citation);
+        }
+    }
+
+    #[test]
+    fn extracts_article_ids() {
+        let cases: [(&str, &[&str]); 8] = [
+            ("Art. 40a OR", &["40a"]),
+            ("Art. 6bis BV", &["6bis"]),
+            ("art. 6 bis al. 2 Cst.", &["6bis"]),
+            ("Art. 6bis 8 BV", &["6bis"]),
+            ("Art. 1 bis 5 ZGB", &["1"]),
+            ("Art. 12f. ZGB", &["12"]),
+            ("Art. 8 BV und Art. 6 EMRK", &["8", "6"]),
+            ("Art. 8 und 9a BV", &["8", "9a"]),
+        ];
+        for (citation, expected) in cases {
+            assert_eq!(extract_article_ids(citation), expected, "{}", citation);
+        }
+    }
+
+    #[test]
+    fn expands_ranges() {
+        let rules = RangeRules { open_range_window: 3, max_range_span: 10, ..RangeRules::default() };
+        let start_only = RangeRules { open_range_mode: OpenRangeMode::StartOnly, ..rules.clone() };
+        let to_end = RangeRules { open_range_mode: OpenRangeMode::ToEndOfLaw, ..rules.clone() };
+        let cases: [(&str, &RangeRules, Option<u32>, &[u32]); 8] = [
+            ("Art. 12", &rules, None, &[12]),
+            ("Art. 1-5", &rules, None, &[1, 2, 3, 4, 5]),
+            ("Art. 1-5", &rules, Some(3), &[1, 2, 3]),
+            ("Art. 1-99", &rules, None, &[1, 99]),
+            ("Art. 41 ff.", &rules, None, &[41, 42, 43, 44]),
+            ("Art. 41 ff.", &start_only, None, &[41]),
+            ("Art. 41 ff.", &to_end, Some(45), &[41, 42, 43, 44, 45]),
+            ("Art. 41 ff.", &to_end, Some(9999), &[41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51]),
+        ];
+        for (citation, rules, max_article, expected) in cases {
+            let mut expanded: Vec<u32> = expand_articles(&extract_article_numbers(citation), rules, max_article)
+                .iter()
+                .map(|provision| provision.number)
+                .collect();
+            expanded.sort_unstable();
+            assert_eq!(expanded, expected, "{}", citation);
+        }
+    }
+
+    #[test]
+    fn converts_roman_numerals() {
+        let cases = [("I", 1), ("IV", 4), ("IX", 9), ("XIV", 14), ("XXXIX", 39), ("XL", 40), ("LXXX", 80), ("XCIV", 94), ("C", 100)];
+        for (numeral, number) in cases {
+            assert_eq!(from_roman(numeral), Some(number), "{}", numeral);
+            assert_eq!(to_roman(number), numeral, "{}", number);
+        }
+        for numeral in ["IIC", "IIII", "VX", "XXXXX", "LL"] {
+            assert_eq!(from_roman(numeral), None, "{}", numeral);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+// `build-reference` subcommand: abbreviation_triplets.json and titles_mapping.json
+// from a local fedlex export, without the network access of the Python builders
+//
+//   cartesian-law-analysis build-reference <export.jsonl|export.parquet> [--out-dir <dir>]
+//
+// The export has one row per act and language with the columns RS_number,
+// Title, Language and Abbreviation (the latter two may be null):
+//
+//   {"RS_number": "101", "Title": "Bundesverfassung ...", "Language": "DE", "Abbreviation": "BV"}
+//
+// As in the Python builders, international law (RS numbers starting with 0)
+// and three-part RS numbers are skipped, and the first title and abbreviation
+// per RS number and language wins. Title keys of title_to_rs are normalised as
+// in build_title_mappings.py (lowercase, single spaces, no trailing
+// punctuation, parentheticals kept), the first RS number per key wins. Null
+// parquet fields count as missing; other non-string fields are skipped with a
+// warning. Maps are written in sorted
+// order, so the same export always gives byte-identical files. A validation
+// report goes to logs/reference_build_report.json.
+use crate::reference::{ByLanguage, DEFAULT_TITLES_PATH, DEFAULT_TRIPLETS_PATH};
+use crate::{arg_value, normalize_abbreviation};
+use parquet::file::reader::SerializedFileReader;
+use parquet::record::Field;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+const REPORT_PATH: &str = "logs/reference_build_report.json";
+
+#[derive(Debug, Default, Deserialize)]
+struct ExportRow {
+    #[serde(rename = "RS_number", alias = "rs_number")]
+    rs_number: Option<String>,
+    #[serde(rename = "Title", alias = "title")]
+    title: Option<String>,
+    #[serde(rename = "Language", alias = "language")]
+    language: Option<String>,
+    #[serde(rename = "Abbreviation", alias = "abbreviation")]
+    abbreviation: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TitlesMapping {
+    titles_by_rs: ByLanguage,
+    title_to_rs: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct BuildReport {
+    rows: usize,
+    skipped_missing_fields: usize,
+    skipped_international: usize,
+    skipped_three_part: usize,
+    rs_numbers_with_titles: usize,
+    rs_numbers_with_abbreviations: usize,
+    /// RS numbers with an abbreviation in FR, DE and IT
+    complete_triplets: usize,
+    titles_by_language: BTreeMap<String, usize>,
+    abbreviations_by_language: BTreeMap<String, usize>,
+    /// Later rows disagreeing with the kept value: "RS 101 DE title: ..."
+    conflicting_values: Vec<String>,
+    /// Normalised abbreviation -> RS numbers using it
+    shared_abbreviations: BTreeMap<String, Vec<String>>,
+    /// Normalised title -> RS numbers using it
+    shared_titles: BTreeMap<String, Vec<String>>,
+}
+
+fn read_jsonl(path: &str) -> Result<Vec<ExportRow>, Box<dyn std::error::Error>> {
+    let reader = BufReader::new(File::open(path).map_err(|e| format!("{}: {}", path, e))?);
+    let mut rows = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        rows.push(serde_json::from_str(&line).map_err(|e| format!("{}:{}: {}", path, idx + 1, e))?);
+    }
+    Ok(rows)
+}
+
+fn read_parquet(path: &str) -> Result<Vec<ExportRow>, Box<dyn std::error::Error>> {
+    let reader = SerializedFileReader::new(File::open(path).map_err(|e| format!("{}: {}", path, e))?)?;
+    let mut rows = Vec::new();
+    // Column -> number of non-string, non-null values skipped
+    let mut non_string: BTreeMap<String, usize> = BTreeMap::new();
+    for record in reader {
+        let record = record?;
+        let mut row = ExportRow::default();
+        for (name, field) in record.get_column_iter() {
+            let slot = match name.to_lowercase().as_str() {
+                "rs_number" => &mut row.rs_number,
+                "title" => &mut row.title,
+                "language" => &mut row.language,
+                "abbreviation" => &mut row.abbreviation,
+                _ => continue,
+            };
+            match field {
+                Field::Str(value) => *slot = Some(value.clone()),
+                Field::Null => {}
+                _ => *non_string.entry(name.clone()).or_default() += 1,
+            }
+        }
+        rows.push(row);
+    }
+    for (column, count) in &non_string {
+        println!("  ⚠ {}: skipped {} non-string values in column {}", path, count, column);
+    }
+    Ok(rows)
+}
+
+/// Title key as built by build_title_mappings.py: "Loi sur le CO2 (Loi sur le CO2)." ->
+/// "loi sur le co2 (loi sur le co2)"
+fn normalize_title_key(title: &str) -> String {
+    title.to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(&['.', ',', ';', ':'][..])
+        .to_string()
+}
+
+/// "0.101" (international law) and "123.456.789" are not in the analysed corpus
+fn is_skipped(rs_number: &str, report: &mut BuildReport) -> bool {
+    if rs_number.starts_with('0') {
+        report.skipped_international += 1;
+        return true;
+    }
+    let parts: Vec<&str> = rs_number.split('.').collect();
+    if parts.len() == 3 && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())) {
+        report.skipped_three_part += 1;
+        return true;
+    }
+    false
+}
+
+/// Keep the first value per RS number and language, recording disagreeing later ones
+fn keep_first(map: &mut ByLanguage, rs_number: &str, language: &str, value: &str, kind: &str, report: &mut BuildReport) {
+    let by_language = map.entry(rs_number.to_string()).or_default();
+    match by_language.get(language) {
+        Some(existing) if existing != value => report.conflicting_values.push(format!(
+            "RS {} {} {}: kept '{}', ignored '{}'", rs_number, language, kind, existing, value
+        )),
+        Some(_) => {}
+        None => {
+            by_language.insert(language.to_string(), value.to_string());
+        }
+    }
+}
+
+/// RS numbers sharing one key, for the report
+fn shared(owners: BTreeMap<String, Vec<String>>) -> BTreeMap<String, Vec<String>> {
+    owners.into_iter()
+        .filter_map(|(key, mut rs_numbers)| {
+            rs_numbers.sort();
+            rs_numbers.dedup();
+            (rs_numbers.len() > 1).then_some((key, rs_numbers))
+        })
+        .collect()
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?);
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Entry point of `build-reference`; `args` follow the subcommand name
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let export_path = args.first()
+        .filter(|arg| !arg.starts_with("--"))
+        .ok_or("usage: build-reference <export.jsonl|export.parquet> [--out-dir <dir>]")?;
+    let out_dir = arg_value(args, "--out-dir").unwrap_or_else(|| ".".to_string());
+
+    println!("\n🏗️  Building reference data from {}...", export_path);
+    let rows = if export_path.ends_with(".parquet") {
+        read_parquet(export_path)?
+    } else {
+        read_jsonl(export_path)?
+    };
+
+    let mut report = BuildReport { rows: rows.len(), ..BuildReport::default() };
+    let mut triplets = ByLanguage::new();
+    let mut titles_by_rs = ByLanguage::new();
+    let mut title_to_rs: BTreeMap<String, String> = BTreeMap::new();
+    let mut abbreviation_owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut title_owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for row in rows {
+        let rs_number = row.rs_number.as_deref().map(str::trim).unwrap_or_default();
+        let language = row.language.as_deref().map(|l| l.trim().to_uppercase()).unwrap_or_default();
+        if rs_number.is_empty() || language.is_empty() {
+            report.skipped_missing_fields += 1;
+            continue;
+        }
+        if is_skipped(rs_number, &mut report) {
+            continue;
+        }
+
+        if let Some(abbreviation) = row.abbreviation.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+            keep_first(&mut triplets, rs_number, &language, abbreviation, "abbreviation", &mut report);
+            abbreviation_owners.entry(normalize_abbreviation(abbreviation)).or_default().push(rs_number.to_string());
+        }
+
+        if let Some(title) = row.title.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            keep_first(&mut titles_by_rs, rs_number, &language, title, "title", &mut report);
+            let normalized = normalize_title_key(title);
+            if !normalized.is_empty() {
+                title_to_rs.entry(normalized.clone()).or_insert_with(|| rs_number.to_string());
+                title_owners.entry(normalized).or_default().push(rs_number.to_string());
+            }
+        }
+    }
+
+    triplets.retain(|_, by_language| !by_language.is_empty());
+    titles_by_rs.retain(|_, by_language| !by_language.is_empty());
+    report.rs_numbers_with_titles = titles_by_rs.len();
+    report.rs_numbers_with_abbreviations = triplets.len();
+    report.complete_triplets = triplets.values()
+        .filter(|by_language| ["FR", "DE", "IT"].iter().all(|language| by_language.contains_key(*language)))
+        .count();
+    for by_language in titles_by_rs.values() {
+        for language in by_language.keys() {
+            *report.titles_by_language.entry(language.clone()).or_default() += 1;
+        }
+    }
+    for by_language in triplets.values() {
+        for language in by_language.keys() {
+            *report.abbreviations_by_language.entry(language.clone()).or_default() += 1;
+        }
+    }
+    report.shared_abbreviations = shared(abbreviation_owners);
+    report.shared_titles = shared(title_owners);
+
+    std::fs::create_dir_all(&out_dir)?;
+    let triplets_path = Path::new(&out_dir).join(DEFAULT_TRIPLETS_PATH);
+    let titles_path = Path::new(&out_dir).join(DEFAULT_TITLES_PATH);
+    write_json(&triplets_path, &triplets)?;
+    write_json(&titles_path, &TitlesMapping { titles_by_rs, title_to_rs })?;
+    std::fs::create_dir_all("logs")?;
+    write_json(Path::new(REPORT_PATH), &report)?;
+
+    println!("  ✓ Read {} rows ({} without RS number or language, {} international, {} three-part RS numbers skipped)",
+             report.rows,
+             report.skipped_missing_fields,
+             report.skipped_international,
+             report.skipped_three_part);
+    println!("  ✓ {} RS numbers with abbreviations ({} complete FR/DE/IT triplets) -> {}",
+             report.rs_numbers_with_abbreviations,
+             report.complete_triplets,
+             triplets_path.display());
+    println!("  ✓ {} RS numbers with titles -> {}", report.rs_numbers_with_titles, titles_path.display());
+    if !report.conflicting_values.is_empty() || !report.shared_abbreviations.is_empty() || !report.shared_titles.is_empty() {
+        println!("  ⚠ {} conflicting values, {} abbreviations and {} titles shared by several RS numbers",
+                 report.conflicting_values.len(),
+                 report.shared_abbreviations.len(),
+                 report.shared_titles.len());
+    }
+    println!("  ✓ Validation report saved to {}", REPORT_PATH);
+    Ok(())
+}
@@ -0,0 +1,177 @@
+// Concordance tables mapping renumbered articles of a former law version to the
+// current one
+//
+// The file is JSON, per RS number a map from old to new article:
+//
+//   {"311.0": {"43": "44", "63": "47"}, "220": {"40a": "40b"}}
+//
+// Former-version citations whose articles all have a concordance entry are
+// renumbered and moved into the current law group, so "Art. 43 aCP" and
+// "Art. 44 CP" overlap in Phase 2. Comparisons work on article numbers, so
+// letter suffixes ("40a") only count by their number: two entries of a law
+// whose old articles share a number ("40" and "40a") are rejected.
+use crate::articles::ArticleRange;
+use crate::versions::{group_key, LawVersion};
+use crate::{AHashMap, CitationInfo};
+use std::collections::HashMap;
+use std::fs::File;
+
+#[derive(Debug, Default)]
+pub struct Concordance {
+    /// RS number -> old article number -> new article number
+    tables: AHashMap<String, AHashMap<u32, u32>>,
+}
+
+/// Leading digits of an article id: "40a" -> 40
+fn article_number(id: &str) -> Option<u32> {
+    let digits: String = id.trim().chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+impl Concordance {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        println!("Loading concordance tables from {}...", path);
+        let file = File::open(path)?;
+        let raw: HashMap<String, HashMap<String, String>> = serde_json::from_reader(file)
+            .map_err(|e| format!("{}: {}", path, e))?;
+
+        let mut tables: AHashMap<String, AHashMap<u32, u32>> = AHashMap::new();
+        for (rs_number, entries) in raw {
+            let mut table = AHashMap::new();
+            // Old article number -> old article id, to report collisions
+            let mut old_ids: AHashMap<u32, String> = AHashMap::new();
+            for (old, new) in entries {
+                let (Some(old_number), Some(new_number)) = (article_number(&old), article_number(&new)) else {
+                    return Err(format!("{}: invalid concordance entry {} -> {} for RS {}", path, old, new, rs_number).into());
+                };
+                if let Some(other) = old_ids.insert(old_number, old.clone()) {
+                    return Err(format!(
+                        "{}: concordance entries {} and {} for RS {} both renumber article {}",
+                        path, other, old, rs_number, old_number
+                    ).into());
+                }
+                table.insert(old_number, new_number);
+            }
+            tables.insert(rs_number, table);
+        }
+
+        let total_entries: usize = tables.values().map(|table| table.len()).sum();
+        println!("  ✓ Loaded {} entries for {} laws", total_entries, tables.len());
+        Ok(Concordance { tables })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// Current numbering of former-version articles; `None` unless every
+    /// article has an entry. Closed ranges are renumbered article by article
+    /// ("Art. 5-8" may end up split), open ranges by their first article
+    fn renumber(&self, law: &str, articles: &[ArticleRange]) -> Option<Vec<ArticleRange>> {
+        let table = self.tables.get(law)?;
+        if articles.is_empty() {
+            return None;
+        }
+
+        let mut numbers = Vec::new();
+        let mut renumbered = Vec::new();
+        for range in articles {
+            if !range.is_plain() {
+                return None;
+            }
+            match range.end {
+                // More articles than entries: some cannot have one
+                Some(end) if (end - range.start) as usize >= table.len() => return None,
+                Some(end) => {
+                    for article in range.start..=end {
+                        numbers.push(*table.get(&article)?);
+                    }
+                }
+                None => renumbered.push(ArticleRange { start: *table.get(&range.start)?, ..*range }),
+            }
+        }
+
+        // Consecutive new numbers back into closed ranges
+        numbers.sort_unstable();
+        numbers.dedup();
+        let mut numbers = numbers.into_iter().peekable();
+        while let Some(start) = numbers.next() {
+            let mut end = start;
+            while numbers.next_if_eq(&(end + 1)).is_some() {
+                end += 1;
+            }
+            renumbered.push(ArticleRange { end: Some(end), ..ArticleRange::single(start) });
+        }
+        renumbered.sort_unstable();
+        renumbered.dedup();
+        Some(renumbered)
+    }
+}
+
+/// Move former-version citations with a full concordance into the current law
+/// group, renumbered. Returns the number of citations moved.
+pub fn apply_concordance(law_groups: &mut AHashMap<String, Vec<CitationInfo>>, concordance: &Concordance) -> usize {
+    println!("\n🔁 Phase 1.4: Renumbering former-version articles with concordance tables...");
+
+    let former_keys: Vec<String> = law_groups.iter()
+        .filter(|(_, citations)| citations.first().is_some_and(|c| c.version == LawVersion::Former))
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    let mut moved = 0;
+    for key in former_keys {
+        let Some(citations) = law_groups.remove(&key) else { continue };
+        let mut remaining = Vec::new();
+        for mut citation in citations {
+            match concordance.renumber(&citation.law, &citation.articles) {
+                Some(articles) => {
+                    citation.articles = articles;
+                    citation.version = LawVersion::Current;
+                    citation.renumbered = true;
+                    law_groups.entry(group_key(&citation.law, LawVersion::Current)).or_default().push(citation);
+                    moved += 1;
+                }
+                None => remaining.push(citation),
+            }
+        }
+        if !remaining.is_empty() {
+            law_groups.insert(key, remaining);
+        }
+    }
+
+    println!("  ✓ Renumbered {} citations into the current law version", moved);
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn concordance(entries: &[(u32, u32)]) -> Concordance {
+        let table = entries.iter().copied().collect();
+        Concordance { tables: [("311.0".to_string(), table)].into_iter().collect() }
+    }
+
+    fn renumbered(concordance: &Concordance, articles: &[ArticleRange]) -> Option<Vec<String>> {
+        concordance.renumber("311.0", articles)
+            .map(|ranges| ranges.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn renumbers_every_article_of_a_range() {
+        let shifted = concordance(&[(5, 6), (6, 7), (7, 8), (8, 9)]);
+        let range = ArticleRange { end: Some(8), ..ArticleRange::single(5) };
+        assert_eq!(renumbered(&shifted, &[range]), Some(vec!["6-9".to_string()]));
+
+        // Article 6 was moved elsewhere: the range splits
+        let moved = concordance(&[(5, 6), (6, 20), (7, 8), (8, 9)]);
+        assert_eq!(renumbered(&moved, &[range]), Some(vec!["6".to_string(), "8-9".to_string(), "20".to_string()]));
+
+        // Article 7 has no entry
+        let partial = concordance(&[(5, 6), (6, 7), (8, 9)]);
+        assert_eq!(renumbered(&partial, &[range]), None);
+
+        let open = ArticleRange { end: None, ..ArticleRange::single(5) };
+        assert_eq!(renumbered(&shifted, &[open]), Some(vec!["6 ff.".to_string()]));
+    }
+}
@@ -0,0 +1,110 @@
+// Parser configuration: everything that changes how citations are resolved
+//
+// Configurations are JSON files; missing fields fall back to the defaults, so
+// a file only needs the settings it changes:
+//
+//   {"name": "no_titles", "title_matching": false}
+use crate::articles::RangeRules;
+use crate::normalization::NormalizationRules;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParserConfig {
+    /// Label used in reports and output file names
+    pub name: String,
+    pub normalization: NormalizationRules,
+    /// Fall back to matching full law titles when no abbreviation is found
+    pub title_matching: bool,
+    /// Share of distinctive words that must appear in a title for citations
+    /// with more than 3 distinctive words (shorter ones need all words)
+    pub title_match_threshold: f64,
+    /// Minimum number of distinctive words before title matching is attempted
+    pub title_min_words: usize,
+    /// Attach fragments like "Abs. 2" to the preceding citation of the same element
+    pub stitch_fragments: bool,
+    /// Resolve still unparseable citations from the surrounding part_content
+    pub context_lookup: bool,
+    /// Minimum number of distinctive words before the context lookup matches titles
+    pub context_title_min_words: usize,
+    /// Share of distinctive words a title must contain in the context lookup,
+    /// for citations with 2-3, 4-6 and 7+ distinctive words
+    pub context_title_thresholds: [f64; 3],
+    /// Split "Art. 8 BV und Art. 6 EMRK" into one citation per law
+    pub split_multi_law: bool,
+    /// Group citations of a former law version ("aCP", decided before the
+    /// revision) apart from the current version
+    pub law_versions: bool,
+    /// Expansion of "Art. 1-5" and "Art. 41 ff." when comparing articles
+    pub article_ranges: RangeRules,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        ParserConfig {
+            name: "default".to_string(),
+            normalization: NormalizationRules::default(),
+            title_matching: true,
+            title_match_threshold: 0.7,
+            title_min_words: 2,
+            stitch_fragments: true,
+            context_lookup: true,
+            context_title_min_words: 2,
+            context_title_thresholds: [0.5, 0.45, 0.4],
+            split_multi_law: true,
+            law_versions: true,
+            article_ranges: RangeRules::default(),
+        }
+    }
+}
+
+impl ParserConfig {
+    /// Citations used exactly as they appear in the dataset: no normalisation,
+    /// stitching, context lookup, splitting or law versions
+    pub fn raw() -> Self {
+        ParserConfig {
+            name: "raw".to_string(),
+            normalization: NormalizationRules::none(),
+            stitch_fragments: false,
+            context_lookup: false,
+            split_multi_law: false,
+            law_versions: false,
+            ..ParserConfig::default()
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = File::open(path)?;
+        let config: ParserConfig = serde_json::from_reader(file)
+            .map_err(|e| format!("{}: {}", path, e))?;
+
+        if config.name.trim().is_empty() {
+            return Err(format!("{}: configuration name must not be empty", path).into());
+        }
+        // The name ends up in file names under logs/
+        if !config.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("{}: configuration name '{}' may only contain A-Z, a-z, 0-9, '_' and '-'", path, config.name).into());
+        }
+        if !(0.0..=1.0).contains(&config.title_match_threshold) {
+            return Err(format!("{}: title_match_threshold must be between 0 and 1", path).into());
+        }
+        if !config.context_title_thresholds.iter().all(|threshold| (0.0..=1.0).contains(threshold)) {
+            return Err(format!("{}: context_title_thresholds must be between 0 and 1", path).into());
+        }
+
+        if config.article_ranges.max_range_span == 0 {
+            return Err(format!("{}: article_ranges.max_range_span must be at least 1", path).into());
+        }
+        // "Art. 41 ff." must not reach further than an explicit range may span
+        if config.article_ranges.open_range_window > config.article_ranges.max_range_span {
+            return Err(format!(
+                "{}: article_ranges.open_range_window ({}) must not exceed max_range_span ({})",
+                path, config.article_ranges.open_range_window, config.article_ranges.max_range_span
+            ).into());
+        }
+
+        println!("Loaded parser configuration '{}' from {}", config.name, path);
+        Ok(config)
+    }
+}
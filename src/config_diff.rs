@@ -0,0 +1,167 @@
+// Per-citation diff between two parser configurations run on the same dataset
+use crate::articles::ArticleRange;
+use crate::versions::group_key;
+use crate::{AHashMap, CitationInfo, ResolutionMethod, UnparseableCitation};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Outcome of Phase 1 for one entry of an element's `articles_de_loi`.
+/// Citations split into several segments list every distinct law and method.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CitationResolution {
+    /// Resolved segments joined by " | "
+    pub citation: String,
+    pub segments: usize,
+    pub laws: Vec<String>,
+    pub methods: Vec<ResolutionMethod>,
+    pub articles: Vec<ArticleRange>,
+}
+
+/// Resolutions keyed by (element id, position in `articles_de_loi`)
+pub type ResolutionMap = BTreeMap<(String, usize), CitationResolution>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Resolved under B only
+    NewlyResolved,
+    /// Resolved under A only
+    NoLongerResolved,
+    /// Dropped (ignored/digit-only) under one configuration only
+    Dropped,
+    LawChanged,
+    ArticlesChanged,
+    /// Same laws and articles, split into a different number of segments
+    SegmentsChanged,
+    /// Same law and articles, found by a different method
+    MethodChanged,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolutionChange {
+    pub element_id: String,
+    pub position: usize,
+    pub change: ChangeKind,
+    pub citation_a: Option<String>,
+    pub citation_b: Option<String>,
+    pub laws_a: Vec<String>,
+    pub laws_b: Vec<String>,
+    pub methods_a: Vec<ResolutionMethod>,
+    pub methods_b: Vec<ResolutionMethod>,
+    pub articles_a: Vec<ArticleRange>,
+    pub articles_b: Vec<ArticleRange>,
+}
+
+/// Snapshot of Phase 1 results before the law groups are consumed by Phase 2
+pub fn collect_resolutions(
+    law_groups: &AHashMap<String, Vec<CitationInfo>>,
+    unparseable: &[UnparseableCitation],
+) -> ResolutionMap {
+    let mut resolutions = ResolutionMap::new();
+    let mut segment_texts: BTreeMap<(String, usize), Vec<&str>> = BTreeMap::new();
+
+    for citation in law_groups.values().flatten() {
+        let key = (citation.element_id.clone(), citation.position);
+        segment_texts.entry(key.clone()).or_default().push(&citation.citation);
+
+        let entry = resolutions.entry(key).or_default();
+        entry.segments += 1;
+        entry.laws.push(group_key(&citation.law, citation.version));
+        entry.methods.push(citation.resolution_method);
+        entry.articles.extend(citation.articles.iter().copied());
+    }
+
+    for (key, mut texts) in segment_texts {
+        texts.sort_unstable();
+        let resolution = resolutions.get_mut(&key).expect("segment key comes from resolutions");
+        resolution.citation = texts.join(" | ");
+        resolution.laws.sort();
+        resolution.laws.dedup();
+        resolution.methods.sort();
+        resolution.methods.dedup();
+        resolution.articles.sort_unstable();
+        resolution.articles.dedup();
+    }
+
+    for citation in unparseable {
+        resolutions
+            .entry((citation.element_id.clone(), citation.position))
+            .or_insert_with(|| CitationResolution {
+                citation: citation.citation.clone(),
+                ..CitationResolution::default()
+            });
+    }
+
+    resolutions
+}
+
+fn classify_change(a: Option<&CitationResolution>, b: Option<&CitationResolution>) -> Option<ChangeKind> {
+    match (a, b) {
+        (None, None) => None,
+        (None, Some(_)) | (Some(_), None) => Some(ChangeKind::Dropped),
+        (Some(a), Some(b)) => match (a.laws.is_empty(), b.laws.is_empty()) {
+            (true, true) => None,
+            (true, false) => Some(ChangeKind::NewlyResolved),
+            (false, true) => Some(ChangeKind::NoLongerResolved),
+            _ if a.laws != b.laws => Some(ChangeKind::LawChanged),
+            _ if a.articles != b.articles => Some(ChangeKind::ArticlesChanged),
+            _ if a.segments != b.segments => Some(ChangeKind::SegmentsChanged),
+            _ if a.methods != b.methods => Some(ChangeKind::MethodChanged),
+            _ => None,
+        },
+    }
+}
+
+pub fn diff_resolutions(a: &ResolutionMap, b: &ResolutionMap) -> Vec<ResolutionChange> {
+    let mut keys: Vec<&(String, usize)> = a.keys().chain(b.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut changes = Vec::new();
+    for key in keys {
+        let (res_a, res_b) = (a.get(key), b.get(key));
+        if let Some(change) = classify_change(res_a, res_b) {
+            changes.push(ResolutionChange {
+                element_id: key.0.clone(),
+                position: key.1,
+                change,
+                citation_a: res_a.map(|r| r.citation.clone()),
+                citation_b: res_b.map(|r| r.citation.clone()),
+                laws_a: res_a.map(|r| r.laws.clone()).unwrap_or_default(),
+                laws_b: res_b.map(|r| r.laws.clone()).unwrap_or_default(),
+                methods_a: res_a.map(|r| r.methods.clone()).unwrap_or_default(),
+                methods_b: res_b.map(|r| r.methods.clone()).unwrap_or_default(),
+                articles_a: res_a.map(|r| r.articles.clone()).unwrap_or_default(),
+                articles_b: res_b.map(|r| r.articles.clone()).unwrap_or_default(),
+            });
+        }
+    }
+    changes
+}
+
+/// Write changes as JSONL and print a count per change kind
+pub fn write_changes(
+    changes: &[ResolutionChange],
+    output_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::create(output_path)?;
+    let mut writer = BufWriter::new(file);
+    for change in changes {
+        writeln!(writer, "{}", serde_json::to_string(change)?)?;
+    }
+    writer.flush()?;
+
+    let mut per_kind: BTreeMap<ChangeKind, usize> = BTreeMap::new();
+    for change in changes {
+        *per_kind.entry(change.change).or_default() += 1;
+    }
+
+    println!("\n🔀 Citations whose resolution changed: {}", changes.len());
+    for (kind, count) in &per_kind {
+        println!("  • {:<20} {:>8}", format!("{:?}", kind), count);
+    }
+    println!("  ✓ Saved to {}", output_path);
+    Ok(())
+}
@@ -0,0 +1,326 @@
+// Which constitution a citation refers to: federal, cantonal or foreign
+//
+// "Art. 8 BV", "Cst. féd." and "Bundesverfassung" cite the federal
+// constitution (RS 101). "KV ZH", "Cst./GE", "Cost. TI", "Verfassung des
+// Kantons Bern" or "Constitution vaudoise" cite a cantonal one and are grouped
+// per canton: under its RS number when the titles list it ("Verfassung des
+// Kantons Zürich" is RS 131.211), else as "CANTONAL_CST_ZH" ("CANTONAL_CST"
+// when the canton is not named).
+// "Grundgesetz", "B-VG" or "Constitution française" cite a foreign one
+// ("FOREIGN_CST_DE"; "FOREIGN_CST" for an unnamed country). Foreign markers are
+// checked first ("Bundes-Verfassungsgesetz" is Austrian), then cantonal ones;
+// any other constitution keyword or abbreviation is the federal constitution.
+//
+// A mention only counts in law position, right after an article reference
+// ("Art. 8 Abs. 1 der Bundesverfassung", "art. 5 Cst./GE") or right before
+// one ("BV Art. 8"): "Art. 12 ZGB, verfassungskonform ausgelegt" cites the
+// ZGB only, and "KV" or "Verfassung" on their own cite nothing.
+use crate::markers;
+use crate::reference::{ByLanguage, ReferenceData};
+use crate::tokenizer::fold_diacritics;
+use ahash::AHashMap;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub const FEDERAL_CONSTITUTION_RS: &str = "101";
+
+/// Prefix of the law keys of foreign constitutions
+pub const FOREIGN_PREFIX: &str = "FOREIGN_";
+
+/// Canton code -> folded names (DE/FR/IT) and adjectives. Ambiguous short forms
+/// ("Basel", "Appenzell") are left out: the canton then stays unnamed.
+const CANTONS: [(&str, &[&str]); 26] = [
+    ("ZH", &["zurich", "zurigo", "zurcher", "zurichoise"]),
+    ("BE", &["bern", "berne", "berna", "berner", "bernoise"]),
+    ("LU", &["luzern", "lucerne", "lucerna", "luzerner", "lucernoise"]),
+    ("UR", &["uri", "urner"]),
+    ("SZ", &["schwyz", "schwytz", "svitto", "schwyzer"]),
+    ("OW", &["obwalden", "obwald", "obvaldo", "obwaldner"]),
+    ("NW", &["nidwalden", "nidwald", "nidvaldo", "nidwaldner"]),
+    ("GL", &["glarus", "glaris", "glarona", "glarner"]),
+    ("ZG", &["zug", "zoug", "zugo", "zuger"]),
+    ("FR", &["freiburg", "fribourg", "friburgo", "freiburger", "fribourgeoise"]),
+    ("SO", &["solothurn", "soleure", "soletta", "solothurner", "soleuroise"]),
+    ("BS", &["basel-stadt", "bale-ville", "basilea citta"]),
+    ("BL", &["basel-landschaft", "bale-campagne", "basilea campagna", "baselbieter"]),
+    ("SH", &["schaffhausen", "schaffhouse", "sciaffusa", "schaffhauser"]),
+    ("AR", &["appenzell ausserrhoden", "appenzell rhodes-exterieures", "appenzello esterno"]),
+    ("AI", &["appenzell innerrhoden", "appenzell rhodes-interieures", "appenzello interno"]),
+    ("SG", &["st. gallen", "st.gallen", "sankt gallen", "saint-gall", "san gallo", "st. galler"]),
+    ("GR", &["graubunden", "grisons", "grigioni", "bundner", "grisonne"]),
+    ("AG", &["aargau", "argovie", "argovia", "aargauer", "argovienne"]),
+    ("TG", &["thurgau", "thurgovie", "turgovia", "thurgauer", "thurgovienne"]),
+    ("TI", &["tessin", "ticino", "tessiner", "tessinoise", "ticinese"]),
+    ("VD", &["waadt", "vaud", "waadtlander", "vaudoise", "vodese"]),
+    ("VS", &["wallis", "valais", "vallese", "walliser", "valaisanne", "vallesana"]),
+    ("NE", &["neuenburg", "neuchatel", "neuchateloise", "neuenburger"]),
+    ("GE", &["genf", "geneve", "ginevra", "genfer", "genevoise", "ginevrina"]),
+    ("JU", &["jura", "giura", "jurassienne", "jurassische"]),
+];
+
+/// Country code -> markers of its constitution, on folded text
+const FOREIGN_CONSTITUTIONS: [(&str, &str); 8] = [
+    ("DE", r"(?:\bgg )?grundgesetz|deutschen? (?:bundes)?verfassung|constitution allemande|costituzione tedesca"),
+    ("AT", r"\bb-vg\b|bundes-verfassungsgesetz|osterreichischen? (?:bundes)?verfassung|constitution autrichienne|costituzione austriaca"),
+    ("FR", r"franzosischen? verfassung|constitution (?:francaise|de la republique francaise)|costituzione francese"),
+    ("IT", r"italienischen? verfassung|constitution italienne|costituzione (?:italiana|della repubblica italiana)"),
+    ("LI", r"verfassung des furstentums|liechtensteinischen? verfassung|constitution (?:du liechtenstein|liechtensteinoise)"),
+    ("US", r"amerikanischen? verfassung|constitution (?:americaine|des etats-unis|of the united states)|\bu\.?s\.? constitution|costituzione americana"),
+    ("BE", r"belgischen? verfassung|constitution belge|costituzione belga"),
+    ("ES", r"spanischen? verfassung|constitution espagnole|costituzione spagnola|constitucion espanola"),
+];
+
+static FOREIGN_PATTERNS: Lazy<Vec<(&'static str, Regex)>> = Lazy::new(|| {
+    FOREIGN_CONSTITUTIONS.iter()
+        .map(|(country, pattern)| (*country, Regex::new(pattern).unwrap()))
+        .collect()
+});
+
+static UNNAMED_FOREIGN_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"auslandischen? verfassung|constitution etrangere|costituzione (?:estera|straniera)").unwrap()
+});
+
+/// Folded name or adjective of a canton -> canton code
+static CANTON_NAMES: Lazy<AHashMap<&'static str, &'static str>> = Lazy::new(|| {
+    CANTONS.iter()
+        .flat_map(|(code, names)| names.iter().map(move |name| (*name, *code)))
+        .collect()
+});
+
+/// "(?:appenzell ausserrhoden|...|uri)", longest names first
+static CANTON_NAME_ALTERNATION: Lazy<String> = Lazy::new(|| {
+    let mut names: Vec<&str> = CANTON_NAMES.keys().copied().collect();
+    names.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    let alternatives: Vec<String> = names.iter().map(|name| regex::escape(name)).collect();
+    format!("(?:{})", alternatives.join("|"))
+});
+
+static CANTON_NAME_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"\b{}\b", *CANTON_NAME_ALTERNATION)).unwrap()
+});
+
+// "Kantonsverfassung", "Verfassung des Kantons Bern", "Constitution cantonale",
+// "Constitution de la République et canton de Genève", "Cst. cant."
+static CANTONAL_KEYWORD_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"kantonsverfassung|kantonalen? verfassung|verfassung des kantons|",
+        r"verfassung (?:von republik und|der republik und des) kantons?|",
+        r"constitution cantonale|constitution (?:du|de la republique et) canton|\bcst\.? ?cant\b|",
+        r"costituzione cantonale|costituzione (?:del|della repubblica e) cantone|\bcost\.? ?cant\b",
+    )).unwrap()
+});
+
+// "Zürcher Kantonsverfassung", "Constitution vaudoise", "Costituzione ticinese"
+static CANTONAL_ADJECTIVE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"\b{0} (?:kantons)?verfassung|\b(?:constitution|costituzione) {0}\b",
+        *CANTON_NAME_ALTERNATION
+    )).unwrap()
+});
+
+// "KV ZH", "Cst./GE", "Cst.-VD", "Cost. TI", "KV" alone; "BV" only in the
+// "BV/ZH" style, "Art. 8 BV ZH-Entscheid" cites the federal constitution
+static CANTONAL_ABBREV_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:(?:KV|Cst|Cste|Cost)\.?\s*[/\-]?\s*\(?([A-Z]{2})\b|BV\s*/\s*([A-Z]{2})\b|KV\b)").unwrap()
+});
+
+// "Bundesverfassung", "Constitution", not "verfassungskonform" or "constitutionnel"
+static FEDERAL_KEYWORD_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:constitution|verfassung|costituzione|constituziun)\b").unwrap()
+});
+
+// Cst./Cste. (FR), BV (DE), Cost. (IT), bare or parenthesised
+static FEDERAL_ABBREV_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:C(?:st|ST|ste|STE|ost|OST)|B[Vv])\b").unwrap()
+});
+
+// Transitional and final parts between an article and its law: "disp. trans.", "Übergangsbestimmungen"
+const PART_WORDS: &str = r"(?i:disp|trans|fin|tit|schlt|(?:u|ü|ue)bergangsbestimmung(?:en)?|schlussbestimmung(?:en)?|dispositions?\s+(?:transitoires?|finales?)|disposizioni\s+(?:transitorie|finali))\.?";
+
+// Text ending in an article reference, before a mention: "Art. 8 Abs. 1 ",
+// "art. 9 et 29 al. 2 de la ", "Art. II-IV ", "Art. IV disp. trans. ", "art. 3 della "
+static BEFORE_MENTION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"\b{}\s*(?:\d+|[IVX]+)[a-z]*(?:\s*(?:{}\.?\s*(?:\d+|[a-z])\b|{}\.?|{}|\d+[a-z]*|[IVX]+\b|(?:bis|ter|quater|und|et|e|oder|ou|o|[a-z])\b|[-à,;.°]))*\s*(?:(?:der|des|dem|de la|de l'|du|de|del|della|dello|di|alten|neuen|ancienne|nouvelle)\b\s*)*\(?$",
+        *markers::ARTICLE, *markers::SUBDIVISION, *markers::FOLLOWING, PART_WORDS
+    )).unwrap()
+});
+
+// Text starting with an article reference, after a mention: " Art. 8", "., art. 8",
+// " fédérale, art. 8" (folded)
+static AFTER_MENTION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^\)?(?:\s*(?:federale|suisse|svizzera|\([^)]*\)))*\s*[.,:]*\s*{}\s*(?:\d|[IVX]+\b)",
+        *markers::ARTICLE
+    )).unwrap()
+});
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constitution {
+    Federal,
+    /// Canton code, `None` when the canton is not named
+    Cantonal(Option<&'static str>),
+    /// Country code, `None` when the country is not named
+    Foreign(Option<&'static str>),
+}
+
+impl Constitution {
+    /// Law group key of the constitution
+    pub fn law_key(&self, reference: &ReferenceData) -> String {
+        match self {
+            Constitution::Federal => FEDERAL_CONSTITUTION_RS.to_string(),
+            Constitution::Cantonal(Some(canton)) => match reference.cantonal_constitutions.get(canton) {
+                Some(rs_number) => rs_number.clone(),
+                None => format!("CANTONAL_CST_{}", canton),
+            },
+            Constitution::Cantonal(None) => "CANTONAL_CST".to_string(),
+            Constitution::Foreign(Some(country)) => format!("{}CST_{}", FOREIGN_PREFIX, country),
+            Constitution::Foreign(None) => format!("{}CST", FOREIGN_PREFIX),
+        }
+    }
+}
+
+fn canton_code(code: &str) -> Option<&'static str> {
+    CANTONS.iter().find(|(canton, _)| *canton == code).map(|(canton, _)| *canton)
+}
+
+/// Start of the word containing byte `index`: "bundesverfassung" for a match of "verfassung"
+fn word_start(text: &str, index: usize) -> usize {
+    text[..index].char_indices().rev()
+        .take_while(|(_, c)| c.is_alphanumeric())
+        .last()
+        .map_or(index, |(start, _)| start)
+}
+
+/// Whether the mention at `start..end` of `text` stands next to an article reference
+fn in_law_position(text: &str, start: usize, end: usize) -> bool {
+    BEFORE_MENTION_PATTERN.is_match(&text[..word_start(text, start)]) || AFTER_MENTION_PATTERN.is_match(&text[end..])
+}
+
+/// First match of `pattern` in `text` that is in law position, when `required`
+fn find_mention<'t>(pattern: &Regex, text: &'t str, required: bool) -> Option<regex::Captures<'t>> {
+    pattern.captures_iter(text).find(|caps| {
+        let mention = caps.get(0).unwrap();
+        !required || in_law_position(text, mention.start(), mention.end())
+    })
+}
+
+/// Canton named anywhere in the folded citation
+fn named_canton(folded: &str) -> Option<&'static str> {
+    CANTON_NAME_PATTERN.find(folded).and_then(|name| CANTON_NAMES.get(name.as_str()).copied())
+}
+
+/// Canton code -> RS number, for the titles starting like "Verfassung des
+/// Kantons Zürich" or "Constitution du canton de Vaud"
+pub fn index_cantonal_constitutions(titles_by_rs: &ByLanguage) -> AHashMap<&'static str, String> {
+    let mut index = AHashMap::new();
+    for (rs_number, titles) in titles_by_rs {
+        for title in titles.values() {
+            let folded = fold_diacritics(title);
+            let is_constitution = CANTONAL_KEYWORD_PATTERN.find(&folded).is_some_and(|keyword| keyword.start() == 0);
+            if let Some(canton) = named_canton(&folded).filter(|_| is_constitution) {
+                index.entry(canton).or_insert_with(|| rs_number.clone());
+            }
+        }
+    }
+    index
+}
+
+/// Constitution cited in law position, if any
+pub fn detect_constitution(citation: &str) -> Option<Constitution> {
+    detect(citation, true)
+}
+
+/// Whether a single word names a constitution ("BV", "Kantonsverfassung"),
+/// wherever it stands; used to find law mentions when segmenting
+pub fn names_constitution(word: &str) -> bool {
+    detect(word, false).is_some()
+}
+
+fn detect(citation: &str, law_position: bool) -> Option<Constitution> {
+    let folded = fold_diacritics(citation);
+
+    for (country, pattern) in FOREIGN_PATTERNS.iter() {
+        if find_mention(pattern, &folded, law_position).is_some() {
+            return Some(Constitution::Foreign(Some(country)));
+        }
+    }
+    if find_mention(&UNNAMED_FOREIGN_PATTERN, &folded, law_position).is_some() {
+        return Some(Constitution::Foreign(None));
+    }
+
+    if find_mention(&CANTONAL_KEYWORD_PATTERN, &folded, law_position).is_some()
+        || find_mention(&CANTONAL_ADJECTIVE_PATTERN, &folded, law_position).is_some()
+    {
+        return Some(Constitution::Cantonal(named_canton(&folded)));
+    }
+    for caps in CANTONAL_ABBREV_PATTERN.captures_iter(citation) {
+        let mention = caps.get(0).unwrap();
+        if law_position && !in_law_position(citation, mention.start(), mention.end()) {
+            continue;
+        }
+        match caps.get(1).or_else(|| caps.get(2)).map(|code| canton_code(code.as_str())) {
+            Some(Some(canton)) => return Some(Constitution::Cantonal(Some(canton))),
+            // "BV SR 101": a federal abbreviation followed by something else
+            Some(None) => {}
+            None => return Some(Constitution::Cantonal(named_canton(&folded))),
+        }
+    }
+
+    if find_mention(&FEDERAL_KEYWORD_PATTERN, &folded, law_position).is_some()
+        || find_mention(&FEDERAL_ABBREV_PATTERN, citation, law_position).is_some()
+    {
+        return Some(Constitution::Federal);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_constitutions_in_law_position() {
+        let cases = [
+            ("Art. 8 BV", Some(Constitution::Federal)),
+            ("art. 29 al. 2 Cst.", Some(Constitution::Federal)),
+            ("Art. 8 Abs. 1 der Bundesverfassung", Some(Constitution::Federal)),
+            ("BV Art. 8", Some(Constitution::Federal)),
+            ("Art. II-IV BV", Some(Constitution::Federal)),
+            ("Art. IV disp. trans. Cst.", Some(Constitution::Federal)),
+            ("Art. 2 Übergangsbestimmungen BV", Some(Constitution::Federal)),
+            ("articolo 8 cpv. 2 Cost.", Some(Constitution::Federal)),
+            ("Art. 5 KV ZH", Some(Constitution::Cantonal(Some("ZH")))),
+            ("art. 12 Cst./GE", Some(Constitution::Cantonal(Some("GE")))),
+            ("Art. 9 BV/ZH", Some(Constitution::Cantonal(Some("ZH")))),
+            ("Art. 8 BV ZH-Entscheid", Some(Constitution::Federal)),
+            ("Art. 8 BV-GE", Some(Constitution::Federal)),
+            ("Art. 5 der Verfassung des Kantons Bern", Some(Constitution::Cantonal(Some("BE")))),
+            ("art. 3 Constitution vaudoise", Some(Constitution::Cantonal(Some("VD")))),
+            ("Art. 5 KV", Some(Constitution::Cantonal(None))),
+            ("Art. 2 Kantonsverfassung", Some(Constitution::Cantonal(None))),
+            ("art. 10 de la constitution cantonale", Some(Constitution::Cantonal(None))),
+            ("Art. 20 des Grundgesetzes", Some(Constitution::Foreign(Some("DE")))),
+            ("Art. 3 GG Grundgesetz", Some(Constitution::Foreign(Some("DE")))),
+            ("art. 3 de la Constitution française", Some(Constitution::Foreign(Some("FR")))),
+            ("Art. 12 der ausländischen Verfassung", Some(Constitution::Foreign(None))),
+            ("Art. 12 ZGB, verfassungskonform ausgelegt", None),
+            ("Art. 12 ZGB gemäss der Verfassung", None),
+            ("KV", None),
+            ("Art. 3 But", None),
+        ];
+        for (citation, expected) in cases {
+            assert_eq!(detect_constitution(citation), expected, "{}", citation);
+        }
+    }
+
+    #[test]
+    fn single_words_name_constitutions_anywhere() {
+        for word in ["BV", "Cst.", "Bundesverfassung", "Kantonsverfassung", "KV"] {
+            assert!(names_constitution(word), "{}", word);
+        }
+        for word in ["ZGB", "But", "verfassungskonform"] {
+            assert!(!names_constitution(word), "{}", word);
+        }
+    }
+}
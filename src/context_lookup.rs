@@ -1,122 +1,45 @@
 // Helper functions for context-aware citation parsing
+use crate::config::ParserConfig;
 use crate::{
-    extract_law_abbreviation, extract_article_numbers, normalize_to_rs_number,
-    AHashMap, AHashSet, AbbrevToRs, CitationInfo, Element, UnparseableCitation,
+    extract_law_abbreviation, lookup_title, normalize_to_rs_number, TitleLookup,
+    AHashMap, AbbrevSource, CitationInfo, Element, ResolutionMethod, UnparseableCitation,
 };
-use serde::Deserialize;
-use std::collections::HashMap;
+use crate::articles::extract_article_numbers;
+use crate::versions::LawVersion;
+use crate::normalization::{normalize_citation, strip_footnote_digits};
+use crate::reference::ReferenceData;
+use crate::title_index::TitleIndex;
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use regex::Regex;
 
-#[derive(Debug, Deserialize)]
-struct TitlesMapping {
-    title_to_rs: HashMap<String, String>,
+/// Returns the matched RS number and the share of key words found in the title;
+/// the word list is shared with Phase 1, the thresholds are the context ones
+fn find_law_by_title(text: &str, title_index: &TitleIndex, config: &ParserConfig) -> Option<(String, f64)> {
+    let (distinctive, candidates) = match lookup_title(&strip_footnote_digits(text), title_index, config.context_title_min_words)? {
+        TitleLookup::Exact(rs) => return Some((rs, 1.0)),
+        TitleLookup::Candidates { distinctive, candidates } => (distinctive, candidates),
+    };
+
+    // Adaptive threshold: longer texts can match with a smaller share of their
+    // words (2-3 words: 50%, 4-6 words: 45%, 7+ words: 40% by default)
+    let [short, medium, long] = config.context_title_thresholds;
+    let threshold = match distinctive {
+        0..=3 => short,
+        4..=6 => medium,
+        _ => long,
+    };
+
+    // Highest-ranked title with enough of the key words
+    candidates.into_iter()
+        .map(|(candidate, matched)| (candidate.rs_number, matched as f64 / distinctive as f64))
+        .find(|(_, ratio)| *ratio >= threshold)
 }
 
-fn load_titles_mapping() -> Option<HashMap<String, String>> {
-    if let Ok(file) = std::fs::File::open("titles_mapping.json") {
-        if let Ok(mapping) = serde_json::from_reader::<_, TitlesMapping>(file) {
-            return Some(mapping.title_to_rs);
-        }
-    }
-    None
-}
-
-fn normalize_text(text: &str) -> String {
-    text.to_lowercase()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
-        .trim_end_matches(&['.', ',', ';', ':'][..])
-        .to_string()
-}
-
-/// Normalize citation text by fixing common patterns
-fn normalize_citation(citation: &str) -> String {
-    let mut result = citation.to_string();
-
-    // Fix pattern 1: "43 aCP" -> "43 a CP" (missing space after digit+a)
-    // Allow mixed case abbreviations like "BauR"
-    let re1 = Regex::new(r"(\d+)\s+a([A-ZÄÖÜ][A-ZÄÖÜa-zäöüß]{1,})\b").unwrap();
-    result = re1.replace_all(&result, "$1 a $2").to_string();
-
-    // Fix pattern 2: " aBauR" -> " a BauR" (missing space in general)
-    // Matches whitespace + lowercase "a" + uppercase letter + any letters (mixed case allowed)
-    let re2 = Regex::new(r"(\s)a([A-ZÄÖÜ][A-ZÄÖÜa-zäöüß]{1,})\b").unwrap();
-    result = re2.replace_all(&result, "$1a $2").to_string();
-
-    result
-}
-
-/// Clean law title by stripping trailing footnote numbers
-fn clean_law_title(title: &str) -> String {
-    // Strip trailing digits (footnote references like "administration3")
-    let re = Regex::new(r"([a-zàâäéèêëïîôùûüÿœæç])\d+$").unwrap();
-    re.replace_all(title, "$1").to_string()
-}
-
-fn find_law_by_title(text: &str, title_to_rs: &HashMap<String, String>) -> Option<String> {
-    // Clean the text (strip footnote numbers, etc.)
-    let cleaned = clean_law_title(text);
-    let normalized = normalize_text(&cleaned);
-
-    // Try exact match first
-    if let Some(rs) = title_to_rs.get(&normalized) {
-        return Some(rs.clone());
-    }
-
-    // Extract key words from text (words longer than 3 chars, excluding common ones)
-    let common_words = ["loi", "ordonnance", "décret", "arrêté", "règlement", "gesetz",
-                        "verordnung", "beschluss", "bundesgesetz", "legge", "ordinanza",
-                        "decreto", "fédérale", "federale", "suisse", "svizzera", "schweiz",
-                        "concernant", "betreffend", "concerning", "über", "sulla", "sur",
-                        "pour", "dans", "avec", "même", "ainsi", "aussi"];
-
-    let text_words: Vec<&str> = normalized
-        .split_whitespace()
-        .filter(|w| w.len() > 3 && !common_words.contains(w))
-        .collect();
-
-    if text_words.len() < 2 {  // Reduced from 3 to allow shorter titles
-        return None; // Too few distinctive words to match reliably
-    }
-
-    // Try fuzzy matching: if most key words from text appear in title
-    let mut best_match: Option<(String, usize)> = None;
-
-    for (title, rs) in title_to_rs.iter() {
-        if title.len() < 20 {  // Reduced from 30 to catch more titles
-            continue;
-        }
-
-        // Count how many text words appear in this title
-        let matching_words = text_words.iter()
-            .filter(|&&word| title.contains(word))
-            .count();
-
-        // Adaptive threshold: longer titles can match with fewer words
-        // 2-3 words: 50%, 4-6 words: 45%, 7+ words: 40%
-        let threshold = if text_words.len() <= 3 {
-            0.5
-        } else if text_words.len() <= 6 {
-            0.45
-        } else {
-            0.4
-        };
-
-        if matching_words as f64 / text_words.len() as f64 >= threshold {
-            if let Some((_, prev_count)) = best_match {
-                if matching_words > prev_count {
-                    best_match = Some((rs.clone(), matching_words));
-                }
-            } else {
-                best_match = Some((rs.clone(), matching_words));
-            }
-        }
-    }
-
-    best_match.map(|(rs, _)| rs)
+/// RS number of the best abbreviation candidate of `text` that is a known law
+fn known_abbreviation(text: &str, reference: &ReferenceData) -> Option<(String, AbbrevSource)> {
+    extract_law_abbreviation(text, reference)?
+        .candidates()
+        .find_map(|candidate| Some((normalize_to_rs_number(&candidate.abbrev, reference, None)?, candidate.source)))
 }
 
 /// Find citation in content and extract surrounding context
@@ -204,9 +127,8 @@ pub fn extract_context_around_citation(citation: &str, content: &str, context_si
 
             if open_parens > close_parens {
                 let mut balance = open_parens - close_parens;
-                for i in citation_end..content_words.len() {
+                for (i, word) in content_words.iter().enumerate().skip(citation_end) {
                     citation_end = i + 1;
-                    let word = content_words[i];
                     for ch in word.chars() {
                         if ch == '(' {
                             balance += 1;
@@ -239,11 +161,12 @@ pub fn extract_context_around_citation(citation: &str, content: &str, context_si
 /// Process unparseable citations by looking up context
 pub fn enrich_with_context(
     elements: &[Element],
-    mut unparseable_list: Vec<UnparseableCitation>,
+    unparseable_list: Vec<UnparseableCitation>,
     law_groups: &mut AHashMap<String, Vec<CitationInfo>>,
-    abbrev_to_rs: &AbbrevToRs,
+    reference: &ReferenceData,
+    config: &ParserConfig,
 ) -> Vec<UnparseableCitation> {
-    println!("\n🔍 Phase 1.5: Enriching fragments with context from part_content...");
+    println!("\n🔍 Phase 1.3: Resolving unparseable citations with context from part_content...");
 
     // Ensure logs directory exists
     std::fs::create_dir_all("logs").ok();
@@ -253,12 +176,11 @@ pub fn enrich_with_context(
         .expect("Failed to create logs/rescued_citations.txt");
     let mut rescued_writer = BufWriter::new(rescued_file);
 
-    // Load title mappings
-    let title_to_rs = load_titles_mapping();
-    if let Some(ref mapping) = title_to_rs {
-        println!("  ✓ Loaded {} law titles for matching", mapping.len());
+    let title_index = reference.title_index.as_ref();
+    if let Some(index) = title_index {
+        println!("  ✓ Using {} law titles for matching", index.title_count());
     } else {
-        println!("  ⚠ Could not load titles_mapping.json, skipping title matching");
+        println!("  ⚠ No law titles loaded, skipping title matching");
     }
 
     // Create a map of element_id -> part_content for quick lookup
@@ -273,8 +195,9 @@ pub fn enrich_with_context(
     let mut contexts_found = 0;
     let mut contexts_not_found = 0;
 
-    for (idx, unparseable) in unparseable_list.into_iter().enumerate() {
-        if unparseable.reason != "no_abbreviation_found" {
+    for unparseable in unparseable_list {
+        // Garbage (repealed markers, dates, page references, ...) has nothing to rescue
+        if unparseable.reason.is_garbage() {
             still_unparseable.push(unparseable);
             continue;
         }
@@ -287,42 +210,43 @@ pub fn enrich_with_context(
                 let mut law_key_opt = None;
 
                 // Normalize the complete citation (fix "43 aCP" -> "43 a CP", etc.)
-                let normalized_citation = normalize_citation(&complete_citation);
-
-                // Try to extract law abbreviation from the normalized citation first
-                if let Some(law_abbrev) = extract_law_abbreviation(&normalized_citation) {
-                    // ONLY accept if it's in the abbreviation triplets (known federal law)
-                    if let Some(rs_number) = normalize_to_rs_number(&law_abbrev, abbrev_to_rs) {
-                        law_key_opt = Some(rs_number);
-                    }
+                let normalized_citation = normalize_citation(&complete_citation, &config.normalization).text;
+
+                // Try the normalized citation first
+                // ONLY accept if it's in the abbreviation triplets (known federal law)
+                if let Some((rs_number, source)) = known_abbreviation(&normalized_citation, reference) {
+                    let confidence = ResolutionMethod::ContextAbbreviation.base_confidence()
+                        * source.confidence_factor();
+                    law_key_opt = Some((rs_number, ResolutionMethod::ContextAbbreviation, confidence));
                 }
 
-                // If not found in citation, try the context
+                // If not found in citation, try the context (less reliable than the citation itself)
                 if law_key_opt.is_none() {
-                    if let Some(law_abbrev) = extract_law_abbreviation(&context) {
-                        if let Some(rs_number) = normalize_to_rs_number(&law_abbrev, abbrev_to_rs) {
-                            law_key_opt = Some(rs_number);
-                        }
+                    if let Some((rs_number, source)) = known_abbreviation(&context, reference) {
+                        let confidence = ResolutionMethod::ContextAbbreviation.base_confidence()
+                            * source.confidence_factor()
+                            * 0.8;
+                        law_key_opt = Some((rs_number, ResolutionMethod::ContextAbbreviation, confidence));
                     }
                 }
 
                 // If abbreviation didn't work, try title matching
                 if law_key_opt.is_none() {
-                    if let Some(ref mapping) = title_to_rs {
-                        // Try title matching on the normalized citation first
-                        if let Some(rs_from_title) = find_law_by_title(&normalized_citation, mapping) {
-                            law_key_opt = Some(rs_from_title);
-                            rescued_by_title += 1;
-                        } else if let Some(rs_from_title) = find_law_by_title(&context, mapping) {
-                            // If not found in citation, try the wider context
-                            law_key_opt = Some(rs_from_title);
+                    if let Some(index) = title_index {
+                        // Try title matching on the normalized citation first,
+                        // then on the wider context
+                        let title_match = find_law_by_title(&normalized_citation, index, config)
+                            .or_else(|| find_law_by_title(&context, index, config));
+                        if let Some((rs_from_title, match_ratio)) = title_match {
+                            let confidence = ResolutionMethod::ContextTitle.base_confidence() * match_ratio;
+                            law_key_opt = Some((rs_from_title, ResolutionMethod::ContextTitle, confidence));
                             rescued_by_title += 1;
                         }
                     }
                 }
 
                 // If we found a law (either by abbreviation or title), add it
-                if let Some(law_key) = law_key_opt {
+                if let Some((law_key, resolution_method, confidence)) = law_key_opt {
                     // Extract articles from the normalized citation
                     let articles = extract_article_numbers(&normalized_citation);
 
@@ -332,11 +256,17 @@ pub fn enrich_with_context(
                         .ok();
 
                     // Add to law groups
-                    law_groups.entry(law_key.clone()).or_insert_with(Vec::new).push(CitationInfo {
+                    law_groups.entry(law_key.clone()).or_default().push(CitationInfo {
                         element_id: unparseable.element_id.clone(),
+                        position: unparseable.position,
+                        segment: 0,
                         citation: normalized_citation,
                         law: law_key,
+                        version: LawVersion::Current,
                         articles,
+                        renumbered: false,
+                        resolution_method,
+                        confidence,
                     });
 
                     rescued += 1;
@@ -0,0 +1,216 @@
+// Existing articles per federal law, from a local fedlex export
+//
+// The export is JSONL with one law per line; articles keep their suffix and
+// repealed articles stay listed so citations to them can be told apart from
+// typos:
+//
+//   {"rs_number": "220", "in_force_since": "2020-01-01",
+//    "articles": [{"number": "40a"}, {"number": "40g", "repealed": true}]}
+//
+// `in_force_since` (optional) is the date the current version came into force;
+// earlier decisions are grouped under the former version of the law.
+//
+// Citations are checked against it after grouping, and "Art. 41 ff." ranges are
+// clamped to the last article of the law.
+use crate::articles::extract_article_ids;
+use crate::versions::{parse_decision_date, LawVersion};
+use crate::{AHashMap, CitationInfo, ResolutionMethod};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+#[derive(Debug, Deserialize)]
+struct ExportArticle {
+    number: String,
+    #[serde(default)]
+    repealed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportLaw {
+    #[serde(alias = "RS_number")]
+    rs_number: String,
+    #[serde(default)]
+    in_force_since: Option<String>,
+    articles: Vec<ExportArticle>,
+}
+
+#[derive(Debug, Default)]
+struct LawArticles {
+    /// Article id ("40a", "6bis") -> repealed
+    articles: AHashMap<String, bool>,
+    max_number: u32,
+    /// ISO date the current version came into force
+    in_force_since: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArticleStatus {
+    Valid,
+    Repealed,
+    /// The law exists but has no such article
+    Unknown,
+}
+
+#[derive(Debug, Default)]
+pub struct LawStructure {
+    laws: AHashMap<String, LawArticles>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArticleIssue {
+    pub element_id: String,
+    pub position: usize,
+    pub citation: String,
+    pub law: String,
+    pub article: String,
+    pub status: ArticleStatus,
+}
+
+/// Lowercase, without spaces: "6 Bis" -> "6bis"
+fn normalize_article_id(id: &str) -> String {
+    id.split_whitespace().collect::<String>().to_lowercase()
+}
+
+/// Leading digits of an article id: "40a" -> 40
+fn article_base_number(id: &str) -> Option<u32> {
+    let digits: String = id.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+impl LawStructure {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        println!("Loading law structure from {}...", path);
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut laws: AHashMap<String, LawArticles> = AHashMap::new();
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let law: ExportLaw = serde_json::from_str(&line)
+                .map_err(|e| format!("{}:{}: {}", path, line_number + 1, e))?;
+
+            let entry = laws.entry(law.rs_number).or_default();
+            if let Some(date) = law.in_force_since {
+                let date = parse_decision_date(&date)
+                    .ok_or_else(|| format!("{}:{}: invalid in_force_since date '{}'", path, line_number + 1, date))?;
+                entry.in_force_since = Some(date);
+            }
+            for article in law.articles {
+                let id = normalize_article_id(&article.number);
+                if let Some(number) = article_base_number(&id) {
+                    entry.max_number = entry.max_number.max(number);
+                }
+                entry.articles.insert(id, article.repealed);
+            }
+        }
+
+        let total_articles: usize = laws.values().map(|law| law.articles.len()).sum();
+        println!("  ✓ Loaded {} laws with {} articles", laws.len(), total_articles);
+        Ok(LawStructure { laws })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.laws.is_empty()
+    }
+
+    /// Highest article number of a law, if the law is in the export
+    pub fn max_article(&self, law: &str) -> Option<u32> {
+        self.laws.get(law).map(|articles| articles.max_number)
+    }
+
+    pub fn in_force_since(&self, law: &str) -> Option<&str> {
+        self.laws.get(law)?.in_force_since.as_deref()
+    }
+
+    /// `None` when the law is not in the export (cantonal laws, treaties, ...)
+    pub fn check(&self, law: &str, article_id: &str) -> Option<ArticleStatus> {
+        let law_articles = self.laws.get(law)?;
+        let id = normalize_article_id(article_id);
+
+        let repealed = match law_articles.articles.get(&id) {
+            Some(&repealed) => repealed,
+            // "Art. 40" when the law only lists "40a"-style variants
+            None if id.chars().all(|c| c.is_ascii_digit()) => {
+                let number = article_base_number(&id);
+                let variants: Vec<bool> = law_articles.articles.iter()
+                    .filter(|(other, _)| article_base_number(other) == number)
+                    .map(|(_, &repealed)| repealed)
+                    .collect();
+                if variants.is_empty() {
+                    return Some(ArticleStatus::Unknown);
+                }
+                variants.iter().all(|&repealed| repealed)
+            }
+            None => return Some(ArticleStatus::Unknown),
+        };
+
+        Some(if repealed { ArticleStatus::Repealed } else { ArticleStatus::Valid })
+    }
+}
+
+/// Flag citations to articles that do not exist or are repealed, and write them
+/// to `output_path`. Returns the number of unknown and repealed articles.
+pub fn validate_articles(
+    law_groups: &AHashMap<String, Vec<CitationInfo>>,
+    structure: &LawStructure,
+    output_path: &str,
+) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+    println!("\n🔎 Phase 1.5: Validating article numbers against the law structure...");
+
+    let mut issues = Vec::new();
+    for (law, citations) in law_groups {
+        for citation in citations {
+            // Stitched fragments repeat the articles of their parent; the
+            // structure describes the main body of the current version only
+            if citation.resolution_method == ResolutionMethod::Stitched || citation.version == LawVersion::Former {
+                continue;
+            }
+            if !citation.articles.iter().all(|range| range.is_plain()) {
+                continue;
+            }
+
+            // Renumbered citations still carry the former numbers in their text
+            let mut article_ids = if citation.renumbered {
+                citation.articles.iter().map(|range| range.start.to_string()).collect()
+            } else {
+                extract_article_ids(&citation.citation)
+            };
+            article_ids.extend(citation.articles.iter().filter_map(|range| range.end).map(|end| end.to_string()));
+            article_ids.sort();
+            article_ids.dedup();
+
+            for article in article_ids {
+                if let Some(status @ (ArticleStatus::Unknown | ArticleStatus::Repealed)) = structure.check(law, &article) {
+                    issues.push(ArticleIssue {
+                        element_id: citation.element_id.clone(),
+                        position: citation.position,
+                        citation: citation.citation.clone(),
+                        law: law.clone(),
+                        article,
+                        status,
+                    });
+                }
+            }
+        }
+    }
+    issues.sort_by(|a, b| a.element_id.cmp(&b.element_id).then(a.position.cmp(&b.position)));
+
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    for issue in &issues {
+        writeln!(writer, "{}", serde_json::to_string(issue)?)?;
+    }
+    writer.flush()?;
+
+    let unknown = issues.iter().filter(|issue| issue.status == ArticleStatus::Unknown).count();
+    let repealed = issues.len() - unknown;
+    println!("  ✓ Unknown articles: {}", unknown);
+    println!("  ✓ Repealed articles: {}", repealed);
+    if !issues.is_empty() {
+        println!("  ✓ Saved to {}", output_path);
+    }
+    Ok((unknown, repealed))
+}
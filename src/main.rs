@@ -3,14 +3,52 @@ use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+mod articles;
+mod build_reference;
+mod concordance;
+mod config;
+mod config_diff;
+mod constitutions;
 mod context_lookup;
+mod law_structure;
+mod markers;
+mod normalization;
+mod overrides;
+mod reference;
+mod reference_diff;
+mod segmentation;
+mod stats_export;
+mod stitching;
+mod stopword_mining;
+mod title_index;
+mod tokenizer;
+mod unparseable;
+mod versions;
+
+use articles::{expand_articles, extract_article_numbers, ArticleRange, Provision, RangeRules};
+use concordance::{apply_concordance, Concordance};
+use config::ParserConfig;
+use config_diff::{collect_resolutions, diff_resolutions, write_changes, ResolutionMap};
+use context_lookup::enrich_with_context;
+use constitutions::{detect_constitution, FOREIGN_PREFIX};
+use law_structure::{validate_articles, LawStructure};
+use normalization::{enrich_article_only, normalize_citation, sole_element_law};
+use overrides::{OverrideTarget, Overrides};
+use reference::{ReferenceData, DEFAULT_ALIASES_PATH, DEFAULT_STOPWORDS_PATH, DEFAULT_TITLES_PATH, DEFAULT_TRIPLETS_PATH};
+use segmentation::segment_citation;
+use stats_export::{write_comparison, write_stats};
+use stitching::stitch_fragments;
+use title_index::{TitleCandidate, TitleIndex};
+use tokenizer::{detect_language, is_month, tokenize, TitleDate};
+use versions::{detect_version, glued_prefixes, group_key, is_former_key, parse_decision_date, LawVersion};
+use unparseable::{classify_unparseable, count_unparseable_reasons, print_unparseable_summary, UnparseableReason};
 
 // ============================================================================
 // TYPES
@@ -22,6 +60,7 @@ struct DatasetRow {
     part_number: Option<String>,
     analysis: Option<String>,
     part_content: Option<String>,
+    decision_date: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -29,6 +68,8 @@ struct Element {
     id: String,
     articles_de_loi: Vec<String>,
     part_content: String,
+    /// ISO date of the decision, when the dataset has one
+    decision_date: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -37,20 +78,119 @@ struct Analysis {
     articles_de_loi: Option<Vec<String>>,
 }
 
+/// How the law of a citation was determined, from most to least reliable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ResolutionMethod {
+    /// Matched an entry of the user-supplied overrides file
+    Override,
+    /// Explicit "RS 220" / "SR 220" reference
+    RsNumber,
+    /// Federal, cantonal or foreign constitution (keyword or Cst./BV/KV-style abbreviation)
+    Constitution,
+    /// Abbreviation found in the abbreviation triplets
+    Abbreviation,
+    /// Fuzzy word matching against `titles_mapping.json`
+    TitleMatch,
+    /// Abbreviation found in the surrounding part_content
+    ContextAbbreviation,
+    /// Title matched in the surrounding part_content
+    ContextTitle,
+    /// Fragment ("Abs. 2", "let. b") attached to the preceding citation of the element
+    Stitched,
+    /// Capitalised word not in the triplets, kept as a CANTONAL_ key
+    CantonalFallback,
+}
+
+impl ResolutionMethod {
+    const ALL: [ResolutionMethod; 9] = [
+        ResolutionMethod::Override,
+        ResolutionMethod::RsNumber,
+        ResolutionMethod::Constitution,
+        ResolutionMethod::Abbreviation,
+        ResolutionMethod::TitleMatch,
+        ResolutionMethod::ContextAbbreviation,
+        ResolutionMethod::ContextTitle,
+        ResolutionMethod::Stitched,
+        ResolutionMethod::CantonalFallback,
+    ];
+
+    /// Same name as in the JSON outputs
+    fn as_str(self) -> &'static str {
+        match self {
+            ResolutionMethod::Override => "override",
+            ResolutionMethod::RsNumber => "rs_number",
+            ResolutionMethod::Constitution => "constitution",
+            ResolutionMethod::Abbreviation => "abbreviation",
+            ResolutionMethod::TitleMatch => "title_match",
+            ResolutionMethod::ContextAbbreviation => "context_abbreviation",
+            ResolutionMethod::ContextTitle => "context_title",
+            ResolutionMethod::Stitched => "stitched",
+            ResolutionMethod::CantonalFallback => "cantonal_fallback",
+        }
+    }
+
+    /// Baseline confidence of a citation resolved by this method
+    fn base_confidence(self) -> f64 {
+        match self {
+            ResolutionMethod::Override => 1.0,
+            ResolutionMethod::RsNumber => 1.0,
+            ResolutionMethod::Constitution => 0.95,
+            ResolutionMethod::Abbreviation => 0.9,
+            ResolutionMethod::TitleMatch => 0.7,
+            ResolutionMethod::ContextAbbreviation => 0.6,
+            ResolutionMethod::ContextTitle => 0.5,
+            // Applied on top of the parent citation's confidence
+            ResolutionMethod::Stitched => 0.9,
+            ResolutionMethod::CantonalFallback => 0.4,
+        }
+    }
+}
+
+/// Where in the citation string `extract_law_abbreviation` found its result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AbbrevSource {
+    RsNumber,
+    Constitution,
+    Parenthesised,
+    Bare,
+}
+
+impl AbbrevSource {
+    /// Confidence penalty for abbreviations that were not explicitly marked
+    fn confidence_factor(self) -> f64 {
+        match self {
+            AbbrevSource::Bare => 0.8,
+            _ => 1.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct CitationInfo {
     element_id: String,
+    /// Index of the citation in the element's `articles_de_loi`
+    position: usize,
+    /// Index of the segment within a split citation, in text order
+    segment: usize,
     citation: String,
     law: String,
-    articles: AHashSet<u32>,
+    version: LawVersion,
+    articles: Vec<ArticleRange>,
+    /// Former-version articles renumbered by the concordance: `articles` no
+    /// longer match the numbers in `citation`
+    renumbered: bool,
+    resolution_method: ResolutionMethod,
+    confidence: f64,
 }
 
 #[derive(Debug, Serialize)]
 struct UnparseableCitation {
     element_id: String,
+    position: usize,
     citation: String,
     extracted_abbrev: Option<String>,
-    reason: String,
+    reason: UnparseableReason,
 }
 
 #[derive(Debug, Serialize)]
@@ -61,9 +201,15 @@ struct CitationAnalysis {
     same_article: bool,
     law1: Option<String>,
     law2: Option<String>,
-    articles1: Vec<u32>,
-    articles2: Vec<u32>,
-    overlapping_articles: Vec<u32>,
+    articles1: Vec<Provision>,
+    articles2: Vec<Provision>,
+    overlapping_articles: Vec<Provision>,
+    resolution1: ResolutionMethod,
+    resolution2: ResolutionMethod,
+    confidence1: f64,
+    confidence2: f64,
+    /// Confidence of the weaker side, used to filter low-confidence edges
+    edge_confidence: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -73,8 +219,9 @@ struct OutputRecord {
     analysis: CitationAnalysis,
 }
 
-type AbbrevTriplets = HashMap<String, HashMap<String, String>>;
 type AbbrevToRs = AHashMap<String, String>;
+/// (law, comparisons, same-article matches) per law group
+type GroupComparisonCounts = Vec<(String, usize, usize)>;
 
 // ============================================================================
 // REGEX PATTERNS (compiled once)
@@ -96,42 +243,52 @@ static PAREN_ABBREV_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"\(([A-ZÄÖÜ][A-ZÄÖÜa-zäöü-]{1,15})\)").unwrap()
 });
 
-static ART_SS_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)[Aa]rt\.?\s*(\d+)\s*(?:ss|ff|sqq?)").unwrap()
-});
-
-static ART_RANGE_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)[Aa]rt\.?\s*(\d+)\s*(?:-|à|bis)\s*(\d+)").unwrap()
-});
-
-static ART_SIMPLE_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)[Aa]rt\.?\s*(\d+)").unwrap()
+static PARENTHESES_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\([^)]*\)").unwrap()
 });
 
 static COMMON_WORDS: Lazy<AHashSet<&'static str>> = Lazy::new(|| {
     [
-        // Article markers
-        "art", "artikel", "article",
-        // Structural markers
-        "abs", "al", "lit", "let", "ch", "bst", "ziff", "satz", "anhang",
+        // Structural markers not in the per-language marker table
+        "satz", "anhang", "allegato",
         // Common words
-        "du", "de", "vom", "der", "des", "und", "et", "bzw", "recte", "ff", "ss",
+        "du", "de", "vom", "der", "des", "und", "et", "bzw", "recte",
+        "della", "del", "dei", "sulla", "sul",
         // Generic terms
         "antrag", "verordnung", "gesetzes", "loi", "constitution", "convention",
         "conseil", "proposition", "tribunal", "gegen", "für", "über",
+        "legge", "ordinanza", "convenzione", "consiglio", "proposta", "tribunale", "lescha",
         // Months (often picked up)
         "januar", "februar", "märz", "april", "mai", "juni", "juli",
         "august", "september", "oktober", "november", "dezember",
         "janvier", "février", "mars", "avril", "mai", "juin", "juillet",
         "août", "septembre", "octobre", "novembre", "décembre",
+        "gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio",
+        "agosto", "settembre", "ottobre", "dicembre",
+        // Transitional and final provisions
+        "schlt", "schlusstitel", "titre", "tit", "disp", "trans", "fin", "final",
         // Other
-        "le", "la", "les", "planungs", "baureglements",
+        "le", "la", "les",
     ]
         .iter()
         .copied()
+        .chain(markers::marker_words())
         .collect()
 });
 
+/// Generic title words (folded, no accents) that do not single out a law in
+/// title matching; shorter words than `MIN_TITLE_WORD_CHARS` never count
+const TITLE_COMMON_WORDS: [&str; 36] = [
+    "loi", "ordonnance", "decret", "arrete", "reglement", "gesetz",
+    "verordnung", "beschluss", "bundesgesetz", "legge", "ordinanza",
+    "decreto", "federale", "suisse", "svizzera", "schweiz",
+    "concernant", "betreffend", "concerning", "uber", "sulla", "sur",
+    "pour", "dans", "avec", "meme", "ainsi", "aussi", "federal",
+    "vom", "della", "del", "relative", "relatif", "relativa", "relativi",
+];
+
+const MIN_TITLE_WORD_CHARS: usize = 5;
+
 // ============================================================================
 // ABBREVIATION HANDLING
 // ============================================================================
@@ -140,126 +297,161 @@ fn normalize_abbreviation(abbrev: &str) -> String {
     abbrev.to_lowercase().replace('.', "").trim().to_string()
 }
 
-fn load_abbreviation_triplets(path: &str) -> Result<(AbbrevTriplets, AbbrevToRs), Box<dyn std::error::Error>> {
-    println!("Loading abbreviation triplets from {}...", path);
-    let file = File::open(path)?;
-    let triplets: AbbrevTriplets = serde_json::from_reader(file)?;
-
-    let mut abbrev_to_rs = AHashMap::new();
-    for (rs_number, langs) in &triplets {
-        for (_lang, abbrev) in langs {
-            let normalized = normalize_abbreviation(abbrev);
-            abbrev_to_rs.entry(normalized).or_insert_with(|| rs_number.clone());
-        }
-    }
-
-    println!("  ✓ Loaded {} RS numbers", triplets.len());
-    println!("  ✓ Mapped {} abbreviations", abbrev_to_rs.len());
-    Ok((triplets, abbrev_to_rs))
-}
-
 // ============================================================================
 // CITATION PARSING
 // ============================================================================
 
-fn extract_law_abbreviation(citation: &str) -> Option<String> {
-    // First try RS/SR patterns
-    if let Some(caps) = RS_PATTERN.captures(citation) {
-        return Some(caps[1].to_string());
+/// A possible law abbreviation of a citation, with its score (higher is more likely)
+#[derive(Debug, Clone, PartialEq)]
+struct AbbrevCandidate {
+    abbrev: String,
+    source: AbbrevSource,
+    score: f64,
+}
+
+impl AbbrevCandidate {
+    /// Explicit RS number or constitution reference: no competing candidates
+    fn certain(abbrev: String, source: AbbrevSource) -> Self {
+        AbbrevCandidate { abbrev, source, score: f64::INFINITY }
     }
-    if let Some(caps) = SR_PATTERN.captures(citation) {
-        return Some(caps[1].to_string());
+}
+
+/// Best candidate of a citation and the other acceptable ones, best first
+#[derive(Debug, Clone)]
+struct AbbrevExtraction {
+    best: AbbrevCandidate,
+    alternatives: Vec<AbbrevCandidate>,
+}
+
+impl AbbrevExtraction {
+    fn single(candidate: AbbrevCandidate) -> Self {
+        AbbrevExtraction { best: candidate, alternatives: Vec::new() }
     }
 
-    // Check for Constitution references (in 3 languages + abbreviations)
-    let citation_lower = citation.to_lowercase();
-    if citation_lower.contains("constitution") ||
-       citation_lower.contains("verfassung") ||
-       citation_lower.contains("costituzione") {
-        return Some("Cst.".to_string());
+    /// Every candidate, best first
+    fn candidates(&self) -> impl Iterator<Item = &AbbrevCandidate> {
+        std::iter::once(&self.best).chain(&self.alternatives)
     }
+}
 
-    // Check for Constitution abbreviations: Cst./Cste. (FR), BV (DE), Cost. (IT), But (variant)
-    for caps in PAREN_ABBREV_PATTERN.captures_iter(citation) {
-        let abbrev = caps[1].to_string();
-        let abbrev_lower = abbrev.to_lowercase();
-        if abbrev_lower == "cst" || abbrev_lower == "cste" ||
-           abbrev_lower == "bv" || abbrev_lower == "cost" ||
-           abbrev_lower == "but" {
-            return Some("Cst.".to_string());
-        }
+// Candidate score weights
+/// In the triplets or aliases
+const KNOWN_ABBREV_SCORE: f64 = 3.0;
+/// Two or more capitals: "StPO", "VwVG", "BGG"
+const ABBREV_SHAPE_SCORE: f64 = 2.0;
+/// Short capitalised word: "Cst", "Cost"
+const SHORT_WORD_SCORE: f64 = 0.5;
+/// Long capitalised word, usually an ordinary noun: "Gesetz", "Planungs"
+const LONG_WORD_SCORE: f64 = -1.0;
+/// Written in parentheses: "(BGG)"
+const PARENTHESISED_SCORE: f64 = 1.5;
+/// First and second candidate after the article number: "Art. 41 OR"
+const AFTER_ARTICLE_SCORES: [f64; 2] = [1.0, 0.5];
+/// Before any article number: "OR Art. 41"
+const BEFORE_ARTICLE_SCORE: f64 = 0.25;
+/// Marker, month or other frequent word
+const COMMON_WORD_SCORE: f64 = -3.0;
+
+static ARTICLE_NUMBER_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d").unwrap());
+
+/// Score of the casing shape of a word
+fn shape_score(word: &str) -> f64 {
+    let capitals = word.chars().filter(|c| c.is_uppercase()).count();
+    if capitals >= 2 {
+        ABBREV_SHAPE_SCORE
+    } else if word.chars().count() <= 4 {
+        SHORT_WORD_SCORE
+    } else {
+        LONG_WORD_SCORE
     }
+}
+
+/// Every capitalised word of the citation scored by casing shape, membership of
+/// the reference data, parentheses, frequent words and position relative to the
+/// article number; candidates with a positive score, best first
+fn score_abbreviation_candidates(citation: &str, reference: &ReferenceData) -> Vec<AbbrevCandidate> {
+    let article_start = ARTICLE_NUMBER_PATTERN.find(citation).map(|m| m.start());
 
-    // Also check without parentheses
+    let mut scored: Vec<(usize, AbbrevCandidate)> = Vec::new();
     for caps in ABBREV_PATTERN.captures_iter(citation) {
-        let abbrev = caps[1].to_string();
-        let abbrev_lower = abbrev.to_lowercase();
-        if abbrev_lower == "cst" || abbrev_lower == "cste" ||
-           abbrev_lower == "bv" || abbrev_lower == "cost" ||
-           abbrev_lower == "but" {
-            return Some("Cst.".to_string());
+        let word = caps.get(1).expect("group 1 always participates");
+        let parenthesised = PAREN_ABBREV_PATTERN.captures_iter(citation)
+            .any(|paren| paren.get(1).is_some_and(|m| m.start() <= word.start() && word.end() <= m.end()));
+
+        let mut score = shape_score(word.as_str());
+        if reference.lookup_abbreviation(word.as_str(), None).is_some() {
+            score += KNOWN_ABBREV_SCORE;
+        }
+        if parenthesised {
+            score += PARENTHESISED_SCORE;
+        }
+        let lowercase = word.as_str().to_lowercase();
+        if COMMON_WORDS.contains(lowercase.as_str()) || reference.learned_stopwords.contains(&lowercase) {
+            score += COMMON_WORD_SCORE;
         }
+        if article_start.is_some_and(|start| word.start() < start) {
+            score += BEFORE_ARTICLE_SCORE;
+        }
+
+        let source = if parenthesised { AbbrevSource::Parenthesised } else { AbbrevSource::Bare };
+        scored.push((word.start(), AbbrevCandidate { abbrev: word.as_str().to_string(), source, score }));
     }
 
-    // Try to find abbreviation in parentheses (high priority)
-    // e.g., "(BGG)", "(StPO)", "(WUB, BS 6 173)"
-    for caps in PAREN_ABBREV_PATTERN.captures_iter(citation) {
-        let abbrev = caps[1].to_string();
-        if !COMMON_WORDS.contains(abbrev.to_lowercase().as_str()) {
-            return Some(abbrev);
+    // Closeness to the article number, counted among the plausible words after it
+    if let Some(start) = article_start {
+        let after = scored.iter_mut()
+            .filter(|(position, candidate)| *position > start && candidate.score > 0.0);
+        for ((_, candidate), bonus) in after.zip(AFTER_ARTICLE_SCORES) {
+            candidate.score += bonus;
         }
     }
 
-    // Fall back to general pattern (look for capitalized words)
-    for caps in ABBREV_PATTERN.captures_iter(citation) {
-        let abbrev = caps[1].to_string();
-        if !COMMON_WORDS.contains(abbrev.to_lowercase().as_str()) {
-            return Some(abbrev);
+    let mut candidates: Vec<AbbrevCandidate> = Vec::new();
+    for (_, candidate) in scored {
+        if candidate.score <= 0.0 {
+            continue;
+        }
+        match candidates.iter_mut().find(|existing| existing.abbrev == candidate.abbrev) {
+            Some(existing) if existing.score < candidate.score => *existing = candidate,
+            Some(_) => {}
+            None => candidates.push(candidate),
         }
     }
-
-    None
+    // Stable: equal scores keep citation order
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates
 }
 
-fn normalize_to_rs_number(abbrev: &str, abbrev_to_rs: &AbbrevToRs) -> Option<String> {
-    // If it's already an RS number, return it
-    if abbrev.chars().all(|c| c.is_numeric() || c == '.') {
-        return Some(abbrev.to_string());
+fn extract_law_abbreviation(citation: &str, reference: &ReferenceData) -> Option<AbbrevExtraction> {
+    // First try RS/SR patterns
+    if let Some(caps) = RS_PATTERN.captures(citation) {
+        return Some(AbbrevExtraction::single(AbbrevCandidate::certain(caps[1].to_string(), AbbrevSource::RsNumber)));
     }
-
-    // Otherwise look it up
-    let normalized = normalize_abbreviation(abbrev);
-    abbrev_to_rs.get(&normalized).cloned()
-}
-
-fn extract_article_numbers(citation: &str) -> AHashSet<u32> {
-    let mut articles = AHashSet::new();
-
-    for caps in ART_SS_PATTERN.captures_iter(citation) {
-        if let Ok(start) = caps[1].parse::<u32>() {
-            for num in start..=(start + 10) {
-                articles.insert(num);
-            }
-        }
+    if let Some(caps) = SR_PATTERN.captures(citation) {
+        return Some(AbbrevExtraction::single(AbbrevCandidate::certain(caps[1].to_string(), AbbrevSource::RsNumber)));
     }
 
-    for caps in ART_RANGE_PATTERN.captures_iter(citation) {
-        if let (Ok(start), Ok(end)) = (caps[1].parse::<u32>(), caps[2].parse::<u32>()) {
-            if start <= end {
-                for num in start..=end {
-                    articles.insert(num);
-                }
-            }
-        }
+    // Federal, cantonal or foreign constitution: the candidate is its law key
+    if let Some(constitution) = detect_constitution(citation) {
+        return Some(AbbrevExtraction::single(AbbrevCandidate::certain(constitution.law_key(reference), AbbrevSource::Constitution)));
     }
 
-    for caps in ART_SIMPLE_PATTERN.captures_iter(citation) {
-        if let Ok(num) = caps[1].parse::<u32>() {
-            articles.insert(num);
-        }
+    // Highest-scoring candidate, the others as alternatives
+    let mut candidates = score_abbreviation_candidates(citation, reference).into_iter();
+    let best = candidates.next()?;
+    Some(AbbrevExtraction { best, alternatives: candidates.collect() })
+}
+
+/// RS number of an abbreviation (aliases valid at `decision_date` first, then
+/// the triplets); any alias counts when the date is unknown
+fn normalize_to_rs_number(abbrev: &str, reference: &ReferenceData, decision_date: Option<&str>) -> Option<String> {
+    // If it's already an RS number, return it
+    if abbrev.chars().all(|c| c.is_numeric() || c == '.') {
+        return Some(abbrev.to_string());
     }
 
-    articles
+    // Otherwise look it up
+    reference.lookup_abbreviation(abbrev, decision_date).map(str::to_string)
 }
 
 // ============================================================================
@@ -279,6 +471,7 @@ fn load_and_filter_dataset(path: &str) -> Result<Vec<Element>, Box<dyn std::erro
 
 fn load_from_csv(path: &str) -> Result<Vec<Element>, Box<dyn std::error::Error>> {
     let mut rdr = csv::Reader::from_path(path)?;
+    let decision_date_column = rdr.headers()?.iter().position(|header| header == "decision_date");
 
     let mut elements = Vec::new();
     let mut total = 0;
@@ -303,10 +496,17 @@ fn load_from_csv(path: &str) -> Result<Vec<Element>, Box<dyn std::error::Error>>
             record.get(4).unwrap_or("{}")
         };
 
-        // part_content not needed for analysis (set to empty)
-        let part_content = String::new();
+        // part_content (for the context lookup) only exists in the original format
+        let part_content = if record.len() >= 6 {
+            record.get(2).unwrap_or("").to_string()
+        } else {
+            String::new()
+        };
+        let decision_date = decision_date_column
+            .and_then(|column| record.get(column))
+            .and_then(parse_decision_date);
 
-        if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_str) {
+        if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(analysis_str) {
             if let Some(articles) = analysis.get("articles de loi").and_then(|v| v.as_array()) {
                 let articles: Vec<String> = articles
                     .iter()
@@ -320,6 +520,7 @@ fn load_from_csv(path: &str) -> Result<Vec<Element>, Box<dyn std::error::Error>>
                         id,
                         articles_de_loi: articles,
                         part_content,
+                        decision_date,
                     });
                 }
             }
@@ -367,10 +568,12 @@ fn load_from_jsonl(path: &str) -> Result<Vec<Element>, Box<dyn std::error::Error
                         let part = row.part_number.unwrap_or_else(|| "0".to_string());
                         let id = format!("{}_{}", row.uuid, part);
                         let part_content = row.part_content.unwrap_or_default();
+                        let decision_date = row.decision_date.as_deref().and_then(parse_decision_date);
                         elements.push(Element {
                             id,
                             articles_de_loi: articles,
                             part_content,
+                            decision_date,
                         });
                     }
                 }
@@ -392,28 +595,9 @@ fn load_from_jsonl(path: &str) -> Result<Vec<Element>, Box<dyn std::error::Error
 // PHASE 1: GROUP BY LAW
 // ============================================================================
 
-fn load_titles_mapping() -> Option<HashMap<String, String>> {
-    if let Ok(file) = std::fs::File::open("titles_mapping.json") {
-        if let Ok(data) = serde_json::from_reader::<_, serde_json::Value>(file) {
-            // The JSON has a "title_to_rs" key with the mapping
-            if let Some(title_to_rs_obj) = data.get("title_to_rs").and_then(|v| v.as_object()) {
-                let mut title_to_rs = HashMap::new();
-                for (title, rs_val) in title_to_rs_obj {
-                    if let Some(rs_number) = rs_val.as_str() {
-                        title_to_rs.insert(title.clone(), rs_number.to_string());
-                    }
-                }
-                return Some(title_to_rs);
-            }
-        }
-    }
-    None
-}
-
 fn normalize_title_for_matching(text: &str) -> String {
     // Remove parenthetical expressions like (PA), (SR 123.45)
-    let re = regex::Regex::new(r"\([^)]*\)").unwrap();
-    let without_parens = re.replace_all(text, "");
+    let without_parens = PARENTHESES_PATTERN.replace_all(text, "");
 
     without_parens
         .to_lowercase()
@@ -424,162 +608,340 @@ fn normalize_title_for_matching(text: &str) -> String {
         .to_string()
 }
 
-fn find_law_by_title_direct(citation: &str, title_to_rs: &HashMap<String, String>) -> Option<String> {
+/// Ranked title candidates considered per citation
+const TITLE_CANDIDATES: usize = 10;
+
+/// Result of looking a title-like citation up in the title index
+enum TitleLookup {
+    /// The citation is a full title
+    Exact(String),
+    /// Ranked titles containing some of the citation's distinctive words
+    Candidates {
+        /// Key words plus the enactment date, if any
+        distinctive: usize,
+        /// Best first, with the number of distinctive words each title contains
+        candidates: Vec<(TitleCandidate, usize)>,
+    },
+}
+
+/// Exact title or ranked candidates for a citation; `None` with fewer than
+/// `min_words` distinctive words. Phase 1 and the context lookup share the
+/// word list and apply their own thresholds to the candidates.
+fn lookup_title(citation: &str, title_index: &TitleIndex, min_words: usize) -> Option<TitleLookup> {
     let normalized_citation = normalize_title_for_matching(citation);
 
     // Try exact match first
-    if let Some(rs) = title_to_rs.get(&normalized_citation) {
-        return Some(rs.clone());
+    if let Some(rs) = title_index.exact_match(&normalized_citation) {
+        return Some(TitleLookup::Exact(rs.to_string()));
     }
 
-    // Extract key words from citation (words longer than 4 chars, excluding common ones)
-    let common_words = [
-        "loi", "ordonnance", "décret", "arrêté", "règlement", "gesetz",
-        "verordnung", "beschluss", "bundesgesetz", "legge", "ordinanza",
-        "decreto", "fédérale", "federale", "suisse", "svizzera", "schweiz",
-        "concernant", "betreffend", "concerning", "über", "sulla", "sur",
-        "pour", "dans", "avec", "même", "ainsi", "aussi", "dans", "fédéral",
-        "federal", "vom", "della", "del", "sulla", "relative", "relatif",
-        "relative", "relativa", "relativi"
-    ];
-
-    let citation_words: Vec<&str> = normalized_citation
-        .split_whitespace()
-        .filter(|w| w.len() > 4 && !common_words.contains(w))
+    // Key words of the citation: long enough and not generic
+    let tokens = tokenize(&normalized_citation);
+    let language = detect_language(&tokens);
+    let mut citation_words: Vec<String> = tokens
+        .into_iter()
+        .filter(|w| w.chars().count() >= MIN_TITLE_WORD_CHARS && !TITLE_COMMON_WORDS.contains(&w.as_str()) && !is_month(w))
         .collect();
+    citation_words.sort_unstable();
+    citation_words.dedup();
 
-    // Need at least 2 distinctive words for matching
-    if citation_words.len() < 2 {
+    // An enactment date ("du 20 décembre 1968") counts as one more distinctive word
+    let date = TitleDate::parse(&normalized_citation);
+    let distinctive = citation_words.len() + usize::from(date.is_some());
+    if distinctive < min_words.max(1) {
         return None;
     }
 
-    // Try fuzzy matching
-    let mut best_match: Option<(String, usize)> = None;
+    let candidates = title_index.search(&citation_words, date, language, TITLE_CANDIDATES)
+        .into_iter()
+        .map(|candidate| {
+            let matched = candidate.matched_words + usize::from(candidate.date_matched);
+            (candidate, matched)
+        })
+        .collect();
+    Some(TitleLookup::Candidates { distinctive, candidates })
+}
 
-    for (title, rs) in title_to_rs.iter() {
-        // Skip very short titles
-        if title.len() < 20 {
-            continue;
-        }
+/// Returns the matched RS number and the share of distinctive citation words found
+/// in the title, lowered when another title scores almost as high
+fn find_law_by_title_direct(
+    citation: &str,
+    title_index: &TitleIndex,
+    config: &ParserConfig,
+) -> Option<(String, f64)> {
+    // Need enough distinctive words for matching (2 by default)
+    let (distinctive, candidates) = match lookup_title(citation, title_index, config.title_min_words)? {
+        TitleLookup::Exact(rs) => return Some((rs, 1.0)),
+        TitleLookup::Candidates { distinctive, candidates } => (distinctive, candidates),
+    };
 
-        let matching_words = citation_words.iter()
-            .filter(|&&word| title.contains(word))
-            .count();
+    // For short citations (2-3 words), require all words to match
+    // For longer citations, require at least the configured share (70% by default)
+    let required_matches = if distinctive <= 3 {
+        distinctive
+    } else {
+        ((distinctive as f64 * config.title_match_threshold).ceil() as usize).max(2)
+    };
 
-        // For short citations (2-3 words), require all words to match
-        // For longer citations, require at least 70% match
-        let required_matches = if citation_words.len() <= 3 {
-            citation_words.len()
-        } else {
-            ((citation_words.len() as f64 * 0.7).ceil() as usize).max(2)
-        };
+    // Highest-scoring title with enough of the citation words
+    let mut candidates = candidates.into_iter().filter(|(_, matched)| *matched >= required_matches);
+    let (best, matched) = candidates.next()?;
+    let mut match_ratio = matched as f64 / distinctive as f64;
 
-        if matching_words >= required_matches {
-            if let Some((_, prev_count)) = best_match {
-                if matching_words > prev_count {
-                    best_match = Some((rs.clone(), matching_words));
-                }
-            } else {
-                best_match = Some((rs.clone(), matching_words));
-            }
+    // Another law scoring almost as high: the words do not single out one title
+    if candidates.next().is_some_and(|(runner_up, _)| runner_up.score >= 0.9 * best.score) {
+        match_ratio *= 0.8;
+    }
+    Some((best.rs_number, match_ratio))
+}
+
+/// Law key of a federal act (an RS number), as opposed to CANTONAL_ and FOREIGN_ keys
+fn is_federal_law(law_key: &str) -> bool {
+    !law_key.starts_with("CANTONAL_") && !law_key.starts_with(FOREIGN_PREFIX)
+}
+
+/// Law key, resolution method and confidence of one citation (or citation segment)
+fn resolve_law(
+    citation: &str,
+    decision_date: Option<&str>,
+    reference: &ReferenceData,
+    config: &ParserConfig,
+) -> Option<(String, ResolutionMethod, f64)> {
+    if let Some(extraction) = extract_law_abbreviation(citation, reference) {
+        if extraction.best.source == AbbrevSource::Constitution {
+            let method = ResolutionMethod::Constitution;
+            return Some((extraction.best.abbrev, method, method.base_confidence()));
         }
+        // Best candidate that is a known law at the decision date, else the best one
+        let resolved = extraction.candidates()
+            .find_map(|candidate| Some((candidate, normalize_to_rs_number(&candidate.abbrev, reference, decision_date)?)));
+        let (law_abbrev, source) = match &resolved {
+            Some((candidate, _)) => (&candidate.abbrev, candidate.source),
+            None => (&extraction.best.abbrev, extraction.best.source),
+        };
+        let (law_key, method) = match resolved.map(|(_, rs_number)| rs_number) {
+            // Federal law - use RS number
+            Some(rs_number) => {
+                let method = match source {
+                    AbbrevSource::RsNumber => ResolutionMethod::RsNumber,
+                    _ => ResolutionMethod::Abbreviation,
+                };
+                (rs_number, method)
+            }
+            // Not in RS mapping - treat as cantonal/regional law
+            // Use "CANTONAL_" prefix to distinguish from federal laws
+            None => (
+                format!("CANTONAL_{}", law_abbrev.to_uppercase()),
+                ResolutionMethod::CantonalFallback,
+            ),
+        };
+        return Some((law_key, method, method.base_confidence() * source.confidence_factor()));
     }
 
-    best_match.map(|(rs, _)| rs)
+    // No abbreviation found - try title matching
+    if !config.title_matching {
+        return None;
+    }
+    let (rs_number, match_ratio) = find_law_by_title_direct(citation, reference.title_index.as_ref()?, config)?;
+    Some((
+        rs_number,
+        ResolutionMethod::TitleMatch,
+        ResolutionMethod::TitleMatch.base_confidence() * match_ratio,
+    ))
 }
 
 fn group_citations_by_law(
     elements: &[Element],
-    abbrev_to_rs: &AbbrevToRs,
-) -> (AHashMap<String, Vec<CitationInfo>>, Vec<UnparseableCitation>) {
+    reference: &ReferenceData,
+    overrides: &Overrides,
+    law_structure: &LawStructure,
+    config: &ParserConfig,
+) -> (AHashMap<String, Vec<CitationInfo>>, Vec<UnparseableCitation>, Phase1Counts) {
     println!("\n🗂️  Phase 1: Grouping citations by law...");
 
-    // Load title mappings for direct title matching
-    let title_to_rs = load_titles_mapping();
-    if let Some(ref mapping) = title_to_rs {
-        println!("  ✓ Loaded {} law titles for direct matching", mapping.len());
-    }
-
     let mut law_groups: AHashMap<String, Vec<CitationInfo>> = AHashMap::new();
     let mut unparseable_list = Vec::new();
     let mut total_citations = 0;
     let mut unparseable = 0;
     let mut matched_by_title = 0;
+    let mut matched_by_override = 0;
+    let mut ignored_by_override = 0;
+    let mut normalized_count = 0;
+    let mut dropped_digit_only = 0;
+    let mut dropped_garbage = 0;
+    let mut dropped_reasons: BTreeMap<UnparseableReason, usize> = BTreeMap::new();
+    let mut enriched_article_only = 0;
+    let mut split_citations = 0;
+    let mut former_versions = 0;
+    let version_of = |citation: &str, raw_citation: &str, law: &str, element: &Element| {
+        if is_former_key(law) {
+            return LawVersion::Former;
+        }
+        if !config.law_versions {
+            return LawVersion::Current;
+        }
+        let glued = glued_prefixes(raw_citation);
+        detect_version(citation, &glued, element.decision_date.as_deref(), law_structure.in_force_since(law))
+    };
 
     for element in elements {
-        for citation in &element.articles_de_loi {
-            total_citations += 1;
-
-            let law_abbrev_opt = extract_law_abbreviation(citation);
-
-            if let Some(law_abbrev) = law_abbrev_opt.as_ref() {
-                if let Some(rs_number) = normalize_to_rs_number(law_abbrev, abbrev_to_rs) {
-                    // Federal law - use RS number
-                    let articles = extract_article_numbers(citation);
-
-                    law_groups.entry(rs_number.clone()).or_insert_with(Vec::new).push(CitationInfo {
-                        element_id: element.id.clone(),
-                        citation: citation.clone(),
-                        law: rs_number,
-                        articles,
-                    });
-                } else {
-                    // Not in RS mapping - treat as cantonal/regional law
-                    // Use "CANTONAL_" prefix to distinguish from federal laws
-                    let cantonal_key = format!("CANTONAL_{}", law_abbrev.to_uppercase());
-                    let articles = extract_article_numbers(citation);
+        let normalized_citations: Vec<_> = element.articles_de_loi.iter()
+            .map(|raw_citation| normalize_citation(raw_citation, &config.normalization))
+            .collect();
+        // "art. 128" next to "Art. 8 ZGB" in the same element cites the ZGB
+        let element_law = if config.normalization.enrich_article_only {
+            sole_element_law(normalized_citations.iter()
+                .filter(|normalized| !normalized.digit_only && normalized.garbage.is_none())
+                .map(|normalized| normalized.text.as_str()))
+        } else {
+            None
+        };
 
-                    law_groups.entry(cantonal_key.clone()).or_insert_with(Vec::new).push(CitationInfo {
+        for (position, raw_citation) in element.articles_de_loi.iter().enumerate() {
+            // User overrides take precedence over every heuristic below
+            match overrides.lookup(raw_citation) {
+                Some(OverrideTarget::Ignore) => {
+                    ignored_by_override += 1;
+                    continue;
+                }
+                Some(OverrideTarget::Law(law_key)) => {
+                    total_citations += 1;
+                    matched_by_override += 1;
+                    let version = version_of(raw_citation, raw_citation, law_key, element);
+                    if version == LawVersion::Former {
+                        former_versions += 1;
+                    }
+                    law_groups.entry(group_key(law_key, version)).or_default().push(CitationInfo {
                         element_id: element.id.clone(),
-                        citation: citation.clone(),
-                        law: cantonal_key,
-                        articles,
+                        position,
+                        segment: 0,
+                        citation: raw_citation.clone(),
+                        law: law_key.clone(),
+                        version,
+                        articles: extract_article_numbers(raw_citation),
+                        renumbered: false,
+                        resolution_method: ResolutionMethod::Override,
+                        confidence: ResolutionMethod::Override.base_confidence(),
                     });
+                    continue;
                 }
+                None => {}
+            }
+
+            let normalized = &normalized_citations[position];
+            if normalized.digit_only {
+                // Article number without any law context
+                dropped_digit_only += 1;
+                continue;
+            }
+            if let Some(reason) = normalized.garbage {
+                dropped_garbage += 1;
+                *dropped_reasons.entry(reason).or_default() += 1;
+                continue;
+            }
+            if normalized.changed {
+                normalized_count += 1;
+            }
+            let enriched = element_law.as_deref().and_then(|law| enrich_article_only(&normalized.text, law));
+            if enriched.is_some() {
+                enriched_article_only += 1;
+            }
+            let citation = enriched.as_ref().unwrap_or(&normalized.text);
+
+            total_citations += 1;
+
+            // "Art. 8 BV und Art. 6 EMRK" -> one segment per law
+            let segments = if config.split_multi_law {
+                segment_citation(citation, reference)
             } else {
-                // No abbreviation found - try title matching
-                if let Some(ref mapping) = title_to_rs {
-                    if let Some(rs_number) = find_law_by_title_direct(citation, mapping) {
-                        matched_by_title += 1;
-                        let articles = extract_article_numbers(citation);
+                vec![citation.clone()]
+            };
+            if segments.len() > 1 {
+                split_citations += 1;
+            }
 
-                        law_groups.entry(rs_number.clone()).or_insert_with(Vec::new).push(CitationInfo {
+            for (segment_index, segment) in segments.into_iter().enumerate() {
+                match resolve_law(&segment, element.decision_date.as_deref(), reference, config) {
+                    Some((law_key, method, confidence)) => {
+                        if method == ResolutionMethod::TitleMatch {
+                            matched_by_title += 1;
+                        }
+                        let articles = extract_article_numbers(&segment);
+                        let version = version_of(&segment, raw_citation, &law_key, element);
+                        if version == LawVersion::Former {
+                            former_versions += 1;
+                        }
+                        law_groups.entry(group_key(&law_key, version)).or_default().push(CitationInfo {
                             element_id: element.id.clone(),
-                            citation: citation.clone(),
-                            law: rs_number,
+                            position,
+                            segment: segment_index,
+                            citation: segment,
+                            law: law_key,
+                            version,
                             articles,
+                            renumbered: false,
+                            resolution_method: method,
+                            confidence,
+                        });
+                    }
+                    None => {
+                        // Couldn't extract any abbreviation or match by title
+                        unparseable += 1;
+                        unparseable_list.push(UnparseableCitation {
+                            element_id: element.id.clone(),
+                            position,
+                            reason: classify_unparseable(&segment),
+                            citation: segment,
+                            extracted_abbrev: None,
                         });
-                        continue;
                     }
                 }
-
-                // Couldn't extract any abbreviation or match by title
-                unparseable += 1;
-                unparseable_list.push(UnparseableCitation {
-                    element_id: element.id.clone(),
-                    citation: citation.clone(),
-                    extracted_abbrev: None,
-                    reason: "no_abbreviation_found".to_string(),
-                });
             }
         }
     }
 
     // Count federal vs cantonal laws
-    let federal_laws = law_groups.keys().filter(|k| !k.starts_with("CANTONAL_")).count();
-    let cantonal_laws = law_groups.keys().filter(|k| k.starts_with("CANTONAL_")).count();
+    let federal_laws = law_groups.keys().filter(|k| is_federal_law(k)).count();
+    let cantonal_laws = law_groups.keys().filter(|k| !is_federal_law(k)).count();
     let parseable_citations = total_citations - unparseable;
 
     println!("  ✓ Total citations: {}", total_citations);
+    if normalized_count > 0 || dropped_digit_only > 0 || dropped_garbage > 0 || enriched_article_only > 0 {
+        println!("    - Normalized: {}", normalized_count);
+        println!("    - Dropped (digit-only): {}", dropped_digit_only);
+        println!("    - Dropped (garbage): {}", dropped_garbage);
+        println!("    - Completed with the element's law: {}", enriched_article_only);
+    }
     println!("  ✓ Successfully parsed: {} ({:.1}%)", parseable_citations, 100.0 * parseable_citations as f64 / total_citations as f64);
     if matched_by_title > 0 {
         println!("    - Matched by title: {}", matched_by_title);
     }
+    if matched_by_override > 0 {
+        println!("    - Matched by override: {}", matched_by_override);
+    }
+    if split_citations > 0 {
+        println!("    - Split into several laws: {}", split_citations);
+    }
+    if former_versions > 0 {
+        println!("    - Citing a former version: {}", former_versions);
+    }
+    if ignored_by_override > 0 {
+        println!("  ✓ Ignored by override: {}", ignored_by_override);
+    }
     println!("  ✓ Unique laws cited: {}", law_groups.len());
     println!("    - Federal laws (RS): {}", federal_laws);
-    println!("    - Cantonal/regional laws: {}", cantonal_laws);
+    println!("    - Cantonal/regional/foreign laws: {}", cantonal_laws);
     println!("  ✓ Unparseable citations: {} ({:.1}%)", unparseable, 100.0 * unparseable as f64 / total_citations as f64);
 
+    let method_counts = count_resolution_methods(&law_groups);
+    println!("\n🧭 Resolution methods:");
+    for (method, method_stats) in &method_counts {
+        println!("  ✓ {:<22} {:>8} (mean confidence {:.2})",
+                 format!("{:?}", method),
+                 format_number(method_stats.count),
+                 method_stats.mean_confidence);
+    }
+
     // Print distribution statistics
     let mut group_sizes: Vec<usize> = law_groups.values().map(|v| v.len()).collect();
     group_sizes.sort_unstable();
@@ -596,143 +958,38 @@ fn group_citations_by_law(
         println!("  ✓ Expected pairwise comparisons: {}", format_number(total_comparisons));
     }
 
-    (law_groups, unparseable_list)
+    let counts = Phase1Counts {
+        total_citations,
+        normalized: normalized_count,
+        dropped_digit_only,
+        dropped_garbage,
+        dropped_reasons,
+        enriched_article_only,
+        ignored_by_override,
+        matched_by_override,
+        matched_by_title,
+        split_citations,
+        former_versions,
+        unparseable,
+        ..Phase1Counts::default()
+    };
+    (law_groups, unparseable_list, counts)
 }
 
-// ============================================================================
-// PHASE 2: WITHIN-GROUP COMPARISON
-// ============================================================================
-
-fn compare_within_groups(
-    law_groups: AHashMap<String, Vec<CitationInfo>>,
-    output_path: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    println!("\n⚡ Phase 2: Comparing citations within each law group...");
-    println!("  Using {} CPU cores\n", rayon::current_num_threads());
-
-    // Calculate total comparisons for progress tracking
-    let total_comparisons: usize = law_groups.values()
-        .map(|citations| citations.len() * (citations.len() - 1) / 2)
-        .sum();
-
-    println!("  Total comparisons to perform: {}\n", format_number(total_comparisons));
-
-    let completed = Arc::new(AtomicUsize::new(0));
-    let same_article_count = Arc::new(AtomicUsize::new(0));
-    let file = File::create(output_path)?;
-    let writer = Arc::new(Mutex::new(BufWriter::new(file)));
-
-    let start_time = Instant::now();
-    let last_print = Arc::new(Mutex::new(Instant::now()));
-
-    // Convert to vec for parallel iteration
-    let groups: Vec<_> = law_groups.into_iter().collect();
-
-    groups.par_iter().for_each(|(law, citations)| {
-        let n = citations.len();
-
-        for i in 0..n {
-            for j in (i + 1)..n {
-                let c1 = &citations[i];
-                let c2 = &citations[j];
-
-                // Skip if same element (element comparing with itself)
-                if c1.element_id == c2.element_id {
-                    continue;
-                }
-
-                // Check article overlap
-                let overlap: AHashSet<_> = c1.articles.intersection(&c2.articles).copied().collect();
-                let has_overlap = !overlap.is_empty();
-
-                if has_overlap {
-                    same_article_count.fetch_add(1, Ordering::Relaxed);
-                }
-
-                // Always write if same law (which they are, by construction)
-                let mut arts1: Vec<_> = c1.articles.iter().copied().collect();
-                let mut arts2: Vec<_> = c2.articles.iter().copied().collect();
-                let mut overlap_vec: Vec<_> = overlap.iter().copied().collect();
-
-                arts1.sort_unstable();
-                arts2.sort_unstable();
-                overlap_vec.sort_unstable();
-
-                let analysis = CitationAnalysis {
-                    citation1: c1.citation.clone(),
-                    citation2: c2.citation.clone(),
-                    same_law: true,
-                    same_article: has_overlap,
-                    law1: Some(law.clone()),
-                    law2: Some(law.clone()),
-                    articles1: arts1,
-                    articles2: arts2,
-                    overlapping_articles: overlap_vec,
-                };
-
-                let record = OutputRecord {
-                    element1: c1.element_id.clone(),
-                    element2: c2.element_id.clone(),
-                    analysis,
-                };
-
-                // Write to output (thread-safe)
-                if let Ok(json) = serde_json::to_string(&record) {
-                    if let Ok(mut w) = writer.lock() {
-                        let _ = writeln!(w, "{}", json);
-                    }
-                }
-
-                // Update progress
-                let current = completed.fetch_add(1, Ordering::Relaxed) + 1;
-
-                // Print progress every 10 seconds
-                if let Ok(mut last) = last_print.try_lock() {
-                    let now = Instant::now();
-                    if now.duration_since(*last) >= Duration::from_secs(10) {
-                        *last = now;
-                        let elapsed = start_time.elapsed().as_secs_f64();
-                        let progress = 100.0 * current as f64 / total_comparisons as f64;
-                        let rate = current as f64 / elapsed;
-                        let remaining = (total_comparisons - current) as f64 / rate;
-                        let same_art = same_article_count.load(Ordering::Relaxed);
-
-                        println!(
-                            "  Progress: {:>5.1}% | Matches: {:>6} ({:.1}%) | Rate: {:>8}/s | ETA: {}",
-                            progress,
-                            format_number(same_art),
-                            100.0 * same_art as f64 / current as f64,
-                            format_number(rate as usize),
-                            format_duration(remaining as u64)
-                        );
-                    }
-                }
-            }
-        }
-    });
-
-    // Flush writer
-    if let Ok(mut w) = writer.lock() {
-        w.flush()?;
+/// Number of citations and mean confidence per resolution method
+fn count_resolution_methods(
+    law_groups: &AHashMap<String, Vec<CitationInfo>>,
+) -> BTreeMap<ResolutionMethod, MethodStats> {
+    let mut totals: BTreeMap<ResolutionMethod, MethodStats> = BTreeMap::new();
+    for citation in law_groups.values().flatten() {
+        let entry = totals.entry(citation.resolution_method).or_default();
+        entry.count += 1;
+        entry.mean_confidence += citation.confidence;
     }
-
-    let elapsed = start_time.elapsed();
-    let total = completed.load(Ordering::Relaxed);
-    let same_article = same_article_count.load(Ordering::Relaxed);
-
-    println!("\n{}", "=".repeat(70));
-    println!("✅ ANALYSIS COMPLETE!");
-    println!("{}", "=".repeat(70));
-    println!("  Total comparisons: {}", format_number(total));
-    println!("  Same article matches: {} ({:.2}%)",
-             format_number(same_article),
-             100.0 * same_article as f64 / total.max(1) as f64);
-    println!("  Time elapsed: {}", format_duration(elapsed.as_secs()));
-    println!("  Average rate: {}/sec", format_number((total as f64 / elapsed.as_secs_f64()) as usize));
-    println!("  Output file: {}", output_path);
-    println!("{}", "=".repeat(70));
-
-    Ok(())
+    for entry in totals.values_mut() {
+        entry.mean_confidence /= entry.count.max(1) as f64;
+    }
+    totals
 }
 
 // ============================================================================
@@ -752,7 +1009,6 @@ fn format_number(n: usize) -> String {
 }
 
 fn format_signed(n: i64) -> String {
-    let sign = if n >= 0 { "+" } else { "" };
     let s = n.abs().to_string();
     let mut result = String::new();
     for (i, c) in s.chars().rev().enumerate() {
@@ -783,8 +1039,63 @@ fn format_duration(secs: u64) -> String {
 // ANALYSIS STATISTICS
 // ============================================================================
 
-#[derive(Debug)]
+/// Citation counts collected while grouping by law
+#[derive(Debug, Default, Serialize)]
+struct Phase1Counts {
+    total_citations: usize,
+    normalized: usize,
+    dropped_digit_only: usize,
+    /// Dates, page references, proposals and cut-off sentences
+    dropped_garbage: usize,
+    /// `dropped_garbage` by category
+    dropped_reasons: BTreeMap<UnparseableReason, usize>,
+    /// Article-only citations completed with the only law of their element
+    enriched_article_only: usize,
+    ignored_by_override: usize,
+    matched_by_override: usize,
+    matched_by_title: usize,
+    /// Citations naming several laws, split into one CitationInfo per law
+    split_citations: usize,
+    /// Citations grouped under the former version of their law
+    former_versions: usize,
+    unparseable: usize,
+    /// Fragments attached to a preceding citation after grouping
+    stitched: usize,
+    /// Citations resolved from the surrounding part_content
+    rescued_by_context: usize,
+    /// Former-version citations moved to the current version by a concordance table
+    renumbered: usize,
+    /// Cited articles missing from the law structure
+    unknown_articles: usize,
+    repealed_articles: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct MethodStats {
+    count: usize,
+    mean_confidence: f64,
+}
+
+/// Group size, comparison results and resolution methods of one law
+#[derive(Debug, Serialize)]
+struct LawStats {
+    law: String,
+    federal: bool,
+    citations: usize,
+    comparisons: usize,
+    same_article_matches: usize,
+    methods: BTreeMap<ResolutionMethod, usize>,
+}
+
+impl LawStats {
+    fn match_rate(&self) -> f64 {
+        100.0 * self.same_article_matches as f64 / self.comparisons.max(1) as f64
+    }
+}
+
+#[derive(Debug, Serialize)]
 struct AnalysisStats {
+    config_name: String,
     file_name: String,
     total_citations: usize,
     parsed_citations: usize,
@@ -794,6 +1105,12 @@ struct AnalysisStats {
     cantonal_laws: usize,
     total_comparisons: usize,
     same_article_matches: usize,
+    /// Citation count and mean confidence per resolution method
+    resolution_methods: BTreeMap<ResolutionMethod, MethodStats>,
+    unparseable_reasons: BTreeMap<UnparseableReason, usize>,
+    phase1: Phase1Counts,
+    /// Sorted by group size, largest first
+    per_law: Vec<LawStats>,
 }
 
 impl AnalysisStats {
@@ -808,15 +1125,32 @@ impl AnalysisStats {
     fn match_rate(&self) -> f64 {
         100.0 * self.same_article_matches as f64 / self.total_comparisons.max(1) as f64
     }
+
+    fn method_count(&self, method: ResolutionMethod) -> usize {
+        self.resolution_methods.get(&method).map_or(0, |method_stats| method_stats.count)
+    }
+
+    fn reason_count(&self, reason: UnparseableReason) -> usize {
+        self.unparseable_reasons.get(&reason).copied().unwrap_or(0)
+    }
+
+    /// Citations of `reason` dropped as garbage during normalisation
+    fn dropped_count(&self, reason: UnparseableReason) -> usize {
+        self.phase1.dropped_reasons.get(&reason).copied().unwrap_or(0)
+    }
 }
 
 fn run_analysis(
     input_file: &str,
-    output_suffix: &str,
-    abbrev_to_rs: &AbbrevToRs,
-) -> Result<AnalysisStats, Box<dyn std::error::Error>> {
+    config: &ParserConfig,
+    reference: &ReferenceData,
+    overrides: &Overrides,
+    law_structure: &LawStructure,
+    concordance: &Concordance,
+) -> Result<(AnalysisStats, ResolutionMap), Box<dyn std::error::Error>> {
+    let output_suffix = &config.name;
     println!("\n{}", "=".repeat(70));
-    println!("📊 ANALYZING: {}", input_file);
+    println!("📊 ANALYZING: {} (configuration '{}')", input_file, config.name);
     println!("{}", "=".repeat(70));
 
     // Load dataset
@@ -827,18 +1161,71 @@ fn run_analysis(
     }
 
     // Phase 1: Group by law
-    let (law_groups, unparseable_citations) = group_citations_by_law(&elements, abbrev_to_rs);
-    let initial_unparseable = unparseable_citations.len();
+    let (mut law_groups, mut unparseable_citations, mut phase1) =
+        group_citations_by_law(&elements, reference, overrides, law_structure, config);
+
+    // Phase 1.2: Attach "Abs. 2"-style fragments to the preceding citation
+    if config.stitch_fragments {
+        let before = unparseable_citations.len();
+        unparseable_citations = stitch_fragments(unparseable_citations, &mut law_groups, output_suffix);
+        phase1.stitched = before - unparseable_citations.len();
+    }
 
-    // Collect statistics
-    let federal_laws = law_groups.keys().filter(|k| !k.starts_with("CANTONAL_")).count();
-    let cantonal_laws = law_groups.keys().filter(|k| k.starts_with("CANTONAL_")).count();
-    let parsed_citations: usize = law_groups.values().map(|v| v.len()).sum();
-    let total_citations = parsed_citations + unparseable_citations.len();
+    // Phase 1.3: Resolve what is left from the surrounding part_content
+    if config.context_lookup {
+        let before = unparseable_citations.len();
+        unparseable_citations = enrich_with_context(
+            &elements,
+            unparseable_citations,
+            &mut law_groups,
+            reference,
+            config,
+        );
+        phase1.rescued_by_context = before - unparseable_citations.len();
+    }
 
     // Create logs directory if it doesn't exist
     std::fs::create_dir_all("logs").ok();
 
+    // Phase 1.4: Renumber former-version articles into the current version
+    if !concordance.is_empty() {
+        phase1.renumbered = apply_concordance(&mut law_groups, concordance);
+    }
+
+    // Phase 1.5: Flag citations to articles that do not exist or were repealed
+    if !law_structure.is_empty() {
+        let issues_path = format!("logs/article_issues_{}.jsonl", output_suffix);
+        let (unknown, repealed) = validate_articles(&law_groups, law_structure, &issues_path)?;
+        phase1.unknown_articles = unknown;
+        phase1.repealed_articles = repealed;
+    }
+
+    // Collect statistics
+    let resolutions = collect_resolutions(&law_groups, &unparseable_citations);
+    let resolution_methods = count_resolution_methods(&law_groups);
+    let unparseable_reasons = count_unparseable_reasons(&unparseable_citations);
+    print_unparseable_summary(&unparseable_citations);
+    let mut per_law: Vec<LawStats> = law_groups.iter()
+        .map(|(law, citations)| {
+            let mut methods = BTreeMap::new();
+            for citation in citations {
+                *methods.entry(citation.resolution_method).or_default() += 1;
+            }
+            LawStats {
+                law: law.clone(),
+                federal: is_federal_law(law),
+                citations: citations.len(),
+                comparisons: 0,
+                same_article_matches: 0,
+                methods,
+            }
+        })
+        .collect();
+    let federal_laws = law_groups.keys().filter(|k| is_federal_law(k)).count();
+    let cantonal_laws = law_groups.keys().filter(|k| !is_federal_law(k)).count();
+    let parsed_citations: usize = law_groups.values().map(|v| v.len()).sum();
+    let total_citations = parsed_citations + unparseable_citations.len();
+
     // Save unparseable citations to file
     let unparseable_file_path = format!("logs/unparseable_citations_{}.jsonl", output_suffix);
     if !unparseable_citations.is_empty() {
@@ -855,9 +1242,24 @@ fn run_analysis(
 
     // Phase 2: Compare within groups
     let output_path = format!("logs/law_citation_matches_{}.jsonl", output_suffix);
-    let (total_comparisons, same_article_matches) = compare_within_groups_stats(law_groups, &output_path)?;
+    let (total_comparisons, same_article_matches, per_group) =
+        compare_within_groups_stats(law_groups, &config.article_ranges, law_structure, &output_path)?;
+
+    overrides.report_and_reset_hits();
+
+    let per_group: AHashMap<String, (usize, usize)> = per_group.into_iter()
+        .map(|(law, comparisons, matches)| (law, (comparisons, matches)))
+        .collect();
+    for law_stats in &mut per_law {
+        if let Some(&(comparisons, matches)) = per_group.get(&law_stats.law) {
+            law_stats.comparisons = comparisons;
+            law_stats.same_article_matches = matches;
+        }
+    }
+    per_law.sort_by(|a, b| b.citations.cmp(&a.citations).then_with(|| a.law.cmp(&b.law)));
 
-    Ok(AnalysisStats {
+    let stats = AnalysisStats {
+        config_name: config.name.clone(),
         file_name: input_file.to_string(),
         total_citations,
         parsed_citations,
@@ -867,13 +1269,22 @@ fn run_analysis(
         cantonal_laws,
         total_comparisons,
         same_article_matches,
-    })
+        resolution_methods,
+        unparseable_reasons,
+        phase1,
+        per_law,
+    };
+    write_stats(&stats)?;
+    Ok((stats, resolutions))
 }
 
+/// Returns total comparisons, same-article matches and (law, comparisons, matches) per group
 fn compare_within_groups_stats(
     law_groups: AHashMap<String, Vec<CitationInfo>>,
+    range_rules: &RangeRules,
+    law_structure: &LawStructure,
     output_path: &str,
-) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+) -> Result<(usize, usize, GroupComparisonCounts), Box<dyn std::error::Error>> {
     println!("\n⚡ Phase 2: Comparing citations within each law group...");
     println!("  Using {} CPU cores\n", rayon::current_num_threads());
 
@@ -893,8 +1304,14 @@ fn compare_within_groups_stats(
 
     let groups: Vec<_> = law_groups.into_iter().collect();
 
-    groups.par_iter().for_each(|(law, citations)| {
+    let per_group: GroupComparisonCounts = groups.par_iter().map(|(law, citations)| {
         let n = citations.len();
+        let max_article = law_structure.max_article(law);
+        let expanded: Vec<AHashSet<Provision>> = citations.iter()
+            .map(|c| expand_articles(&c.articles, range_rules, max_article))
+            .collect();
+        let mut group_comparisons = 0;
+        let mut group_matches = 0;
 
         for i in 0..n {
             for j in (i + 1)..n {
@@ -905,15 +1322,17 @@ fn compare_within_groups_stats(
                     continue;
                 }
 
-                let overlap: AHashSet<_> = c1.articles.intersection(&c2.articles).copied().collect();
+                let overlap: AHashSet<_> = expanded[i].intersection(&expanded[j]).copied().collect();
                 let has_overlap = !overlap.is_empty();
 
+                group_comparisons += 1;
                 if has_overlap {
+                    group_matches += 1;
                     same_article_count.fetch_add(1, Ordering::Relaxed);
                 }
 
-                let mut arts1: Vec<_> = c1.articles.iter().copied().collect();
-                let mut arts2: Vec<_> = c2.articles.iter().copied().collect();
+                let mut arts1: Vec<_> = expanded[i].iter().copied().collect();
+                let mut arts2: Vec<_> = expanded[j].iter().copied().collect();
                 let mut overlap_vec: Vec<_> = overlap.iter().copied().collect();
 
                 arts1.sort_unstable();
@@ -925,11 +1344,16 @@ fn compare_within_groups_stats(
                     citation2: c2.citation.clone(),
                     same_law: true,
                     same_article: has_overlap,
-                    law1: Some(law.clone()),
-                    law2: Some(law.clone()),
+                    law1: Some(c1.law.clone()),
+                    law2: Some(c2.law.clone()),
                     articles1: arts1,
                     articles2: arts2,
                     overlapping_articles: overlap_vec,
+                    resolution1: c1.resolution_method,
+                    resolution2: c2.resolution_method,
+                    confidence1: c1.confidence,
+                    confidence2: c2.confidence,
+                    edge_confidence: c1.confidence.min(c2.confidence),
                 };
 
                 let record = OutputRecord {
@@ -968,7 +1392,9 @@ fn compare_within_groups_stats(
                 }
             }
         }
-    });
+
+        (law.clone(), group_comparisons, group_matches)
+    }).collect();
 
     if let Ok(mut w) = writer.lock() {
         w.flush()?;
@@ -982,113 +1408,241 @@ fn compare_within_groups_stats(
              format_number(same_article),
              100.0 * same_article as f64 / total.max(1) as f64);
 
-    Ok((total, same_article))
+    Ok((total, same_article, per_group))
 }
 
-fn print_comparison(original: &AnalysisStats, preprocessed: &AnalysisStats) {
+fn print_comparison(a: &AnalysisStats, b: &AnalysisStats) {
+    let header = format!("│ {:<21} {:>10}      {:>10}      {:<6}     │",
+                         "", truncate_label(&a.config_name), truncate_label(&b.config_name), "Change");
+    let count_row = |label: &str, before: usize, after: usize| {
+        println!("│ {:<21} {:>10}      {:>10}      {:>6}     │",
+                 label,
+                 format_number(before),
+                 format_number(after),
+                 format_signed(after as i64 - before as i64));
+    };
+
     println!("\n{}", "=".repeat(70));
-    println!("📈 PREPROCESSING IMPACT COMPARISON");
+    println!("📈 CONFIGURATION COMPARISON: {} vs {}", a.config_name, b.config_name);
     println!("{}", "=".repeat(70));
+    println!("  {}: {}", a.config_name, a.file_name);
+    println!("  {}: {}", b.config_name, b.file_name);
 
     println!("\n┌─ PARSING RESULTS ─────────────────────────────────────────────────┐");
-    println!("│                          Original    Preprocessed    Improvement   │");
+    println!("{}", header);
     println!("├───────────────────────────────────────────────────────────────────┤");
-    println!("│ Total Citations       {:>10}      {:>10}                 │",
-             format_number(original.total_citations),
-             format_number(preprocessed.total_citations));
-    println!("│ Successfully Parsed   {:>10}      {:>10}      {:>6}     │",
-             format_number(original.parsed_citations),
-             format_number(preprocessed.parsed_citations),
-             format_signed(preprocessed.parsed_citations as i64 - original.parsed_citations as i64));
+    count_row("Total Citations", a.total_citations, b.total_citations);
+    count_row("Successfully Parsed", a.parsed_citations, b.parsed_citations);
     println!("│ Parsing Rate          {:>9.1}%      {:>9.1}%      {:>+5.1}%    │",
-             original.parsing_rate(),
-             preprocessed.parsing_rate(),
-             preprocessed.parsing_rate() - original.parsing_rate());
-    println!("│ Unparseable           {:>10}      {:>10}      {:>6}     │",
-             format_number(original.unparseable_citations),
-             format_number(preprocessed.unparseable_citations),
-             format_signed(preprocessed.unparseable_citations as i64 - original.unparseable_citations as i64));
+             a.parsing_rate(),
+             b.parsing_rate(),
+             b.parsing_rate() - a.parsing_rate());
+    count_row("Unparseable", a.unparseable_citations, b.unparseable_citations);
+    println!("│ Unparseable Rate      {:>9.1}%      {:>9.1}%      {:>+5.1}%    │",
+             a.unparseable_rate(),
+             b.unparseable_rate(),
+             b.unparseable_rate() - a.unparseable_rate());
+    println!("└───────────────────────────────────────────────────────────────────┘");
+
+    println!("\n┌─ UNPARSEABLE CATEGORIES ──────────────────────────────────────────┐");
+    println!("{}", header);
+    println!("├───────────────────────────────────────────────────────────────────┤");
+    for reason in UnparseableReason::ALL {
+        let (before, after) = (a.reason_count(reason), b.reason_count(reason));
+        if before > 0 || after > 0 {
+            count_row(reason.as_str(), before, after);
+        }
+    }
+    // Dropped during normalisation; the raw configuration reports them above
+    if a.phase1.dropped_garbage + a.phase1.dropped_digit_only + b.phase1.dropped_garbage + b.phase1.dropped_digit_only > 0 {
+        println!("├─ Dropped in Phase 1 ──────────────────────────────────────────────┤");
+        for reason in UnparseableReason::ALL {
+            let (before, after) = (a.dropped_count(reason), b.dropped_count(reason));
+            if before > 0 || after > 0 {
+                count_row(reason.as_str(), before, after);
+            }
+        }
+        count_row("digit-only", a.phase1.dropped_digit_only, b.phase1.dropped_digit_only);
+    }
+    println!("└───────────────────────────────────────────────────────────────────┘");
+
+    println!("\n┌─ RESOLUTION METHODS ──────────────────────────────────────────────┐");
+    println!("{}", header);
+    println!("├───────────────────────────────────────────────────────────────────┤");
+    let methods: std::collections::BTreeSet<_> = a.resolution_methods.keys()
+        .chain(b.resolution_methods.keys())
+        .copied()
+        .collect();
+    for method in methods {
+        count_row(&format!("{:?}", method), a.method_count(method), b.method_count(method));
+    }
     println!("└───────────────────────────────────────────────────────────────────┘");
 
     println!("\n┌─ LAW COVERAGE ────────────────────────────────────────────────────┐");
-    println!("│                          Original    Preprocessed    Change        │");
+    println!("{}", header);
     println!("├───────────────────────────────────────────────────────────────────┤");
-    println!("│ Total Unique Laws     {:>10}      {:>10}      {:>6}     │",
-             format_number(original.unique_laws),
-             format_number(preprocessed.unique_laws),
-             format_signed(preprocessed.unique_laws as i64 - original.unique_laws as i64));
-    println!("│ Federal Laws (RS)     {:>10}      {:>10}      {:>6}     │",
-             format_number(original.federal_laws),
-             format_number(preprocessed.federal_laws),
-             format_signed(preprocessed.federal_laws as i64 - original.federal_laws as i64));
-    println!("│ Cantonal Laws         {:>10}      {:>10}      {:>6}     │",
-             format_number(original.cantonal_laws),
-             format_number(preprocessed.cantonal_laws),
-             format_signed(preprocessed.cantonal_laws as i64 - original.cantonal_laws as i64));
+    count_row("Total Unique Laws", a.unique_laws, b.unique_laws);
+    count_row("Federal Laws (RS)", a.federal_laws, b.federal_laws);
+    count_row("Cantonal Laws", a.cantonal_laws, b.cantonal_laws);
     println!("└───────────────────────────────────────────────────────────────────┘");
 
     println!("\n┌─ COMPARISON ANALYSIS ─────────────────────────────────────────────┐");
-    println!("│                          Original    Preprocessed    Change        │");
+    println!("{}", header);
     println!("├───────────────────────────────────────────────────────────────────┤");
-    println!("│ Total Comparisons     {:>10}      {:>10}      {:>6}     │",
-             format_number(original.total_comparisons),
-             format_number(preprocessed.total_comparisons),
-             format_signed(preprocessed.total_comparisons as i64 - original.total_comparisons as i64));
-    println!("│ Same-Article Matches  {:>10}      {:>10}      {:>6}     │",
-             format_number(original.same_article_matches),
-             format_number(preprocessed.same_article_matches),
-             format_signed(preprocessed.same_article_matches as i64 - original.same_article_matches as i64));
+    count_row("Total Comparisons", a.total_comparisons, b.total_comparisons);
+    count_row("Same-Article Matches", a.same_article_matches, b.same_article_matches);
     println!("│ Match Rate            {:>9.2}%      {:>9.2}%      {:>+5.2}%    │",
-             original.match_rate(),
-             preprocessed.match_rate(),
-             preprocessed.match_rate() - original.match_rate());
+             a.match_rate(),
+             b.match_rate(),
+             b.match_rate() - a.match_rate());
     println!("└───────────────────────────────────────────────────────────────────┘");
 
     // Summary
-    let rescued = (preprocessed.parsed_citations as i64 - original.parsed_citations as i64) as usize;
-    let failure_reduction = if original.unparseable_citations > 0 {
-        100.0 * rescued as f64 / original.unparseable_citations as f64
+    let rescued = b.parsed_citations as i64 - a.parsed_citations as i64;
+    let failure_reduction = if a.unparseable_citations > 0 {
+        100.0 * rescued as f64 / a.unparseable_citations as f64
     } else {
         0.0
     };
 
     println!("\n🎯 KEY INSIGHTS:");
-    println!("  • Citations rescued by preprocessing: {}", format_number(rescued));
+    println!("  • Additional citations parsed by '{}': {}", b.config_name, format_signed(rescued));
     println!("  • Parsing failure reduction: {:.1}%", failure_reduction);
-    println!("  • Additional comparisons enabled: {}", format_signed(preprocessed.total_comparisons as i64 - original.total_comparisons as i64));
-    println!("  • Additional matches discovered: {}", format_signed(preprocessed.same_article_matches as i64 - original.same_article_matches as i64));
+    println!("  • Additional comparisons enabled: {}", format_signed(b.total_comparisons as i64 - a.total_comparisons as i64));
+    println!("  • Additional matches discovered: {}", format_signed(b.same_article_matches as i64 - a.same_article_matches as i64));
 
     println!("\n{}", "=".repeat(70));
 }
 
+/// Fit a configuration name into a 10-character table column
+fn truncate_label(label: &str) -> String {
+    if label.chars().count() <= 10 {
+        label.to_string()
+    } else {
+        format!("{}…", label.chars().take(9).collect::<String>())
+    }
+}
+
 // ============================================================================
 // MAIN
 // ============================================================================
 
+const DEFAULT_INPUT_PATH: &str = "CSVs/data_filtered.csv";
+const DEFAULT_OVERRIDES_PATH: &str = "citation_overrides.json";
+const DEFAULT_LAW_STRUCTURE_PATH: &str = "law_structure.jsonl";
+const DEFAULT_CONCORDANCE_PATH: &str = "concordance.json";
+
+/// Value following `flag` on the command line, e.g. `--overrides my_overrides.json`
+fn arg_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|idx| args.get(idx + 1))
+        .cloned()
+}
+
+/// All values of a repeatable flag, e.g. `--disable-rule-b split_a_prefix --disable-rule-b drop_garbage`
+fn arg_values(args: &[String], flag: &str) -> Vec<String> {
+    args.windows(2)
+        .filter(|pair| pair[0] == flag)
+        .map(|pair| pair[1].clone())
+        .collect()
+}
+
+/// Reference data at the --triplets/--titles/--aliases paths (or the defaults),
+/// with the learned stopwords of --stopwords or the default file if present
+fn load_reference(args: &[String]) -> Result<ReferenceData, Box<dyn std::error::Error>> {
+    let mut reference = ReferenceData::load(
+        &arg_value(args, "--triplets").unwrap_or_else(|| DEFAULT_TRIPLETS_PATH.to_string()),
+        &arg_value(args, "--titles").unwrap_or_else(|| DEFAULT_TITLES_PATH.to_string()),
+        &arg_value(args, "--aliases").unwrap_or_else(|| DEFAULT_ALIASES_PATH.to_string()),
+    )?;
+    match arg_value(args, "--stopwords") {
+        Some(path) => reference.load_stopwords(&path)?,
+        None if std::path::Path::new(DEFAULT_STOPWORDS_PATH).exists() => {
+            reference.load_stopwords(DEFAULT_STOPWORDS_PATH)?
+        }
+        None => {}
+    }
+    Ok(reference)
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    // Subcommands
+    match args.get(1).map(String::as_str) {
+        Some("build-reference") => return build_reference::run(&args[2..]),
+        Some("diff-reference") => return reference_diff::run(&args[2..]),
+        Some("mine-stopwords") => return stopword_mining::run(&args[2..]),
+        _ => {}
+    }
+
     println!("\n{}", "=".repeat(70));
     println!("🚀 CARTESIAN LAW CITATION ANALYSIS - COMPARISON MODE");
     println!("{}", "=".repeat(70));
 
-    // Load abbreviation triplets (shared for both analyses)
-    let (_triplets, abbrev_to_rs) = load_abbreviation_triplets("abbreviation_triplets.json")?;
+    // Abbreviation triplets, aliases and law titles (shared for both analyses)
+    let reference = load_reference(&args)?;
 
-    // Run analysis on original CSV
-    let original_stats = run_analysis(
-        "CSVs/data_filtered.csv",
-        "original",
-        &abbrev_to_rs
-    )?;
+    // Load citation overrides: explicit --overrides path, or the default file if present
+    let overrides = match arg_value(&args, "--overrides") {
+        Some(path) => Overrides::load(&path)?,
+        None if std::path::Path::new(DEFAULT_OVERRIDES_PATH).exists() => {
+            Overrides::load(DEFAULT_OVERRIDES_PATH)?
+        }
+        None => Overrides::default(),
+    };
 
-    // Run analysis on preprocessed CSV
-    let preprocessed_stats = run_analysis(
-        "CSVs/data_filtered_citations_changed.csv",
-        "preprocessed",
-        &abbrev_to_rs
-    )?;
+    // Existing articles per law: explicit --law-structure path, or the default file if present
+    let law_structure = match arg_value(&args, "--law-structure") {
+        Some(path) => LawStructure::load(&path)?,
+        None if std::path::Path::new(DEFAULT_LAW_STRUCTURE_PATH).exists() => {
+            LawStructure::load(DEFAULT_LAW_STRUCTURE_PATH)?
+        }
+        None => LawStructure::default(),
+    };
+
+    // Old -> new article numbers per law: explicit --concordance path, or the default file if present
+    let concordance = match arg_value(&args, "--concordance") {
+        Some(path) => Concordance::load(&path)?,
+        None if std::path::Path::new(DEFAULT_CONCORDANCE_PATH).exists() => {
+            Concordance::load(DEFAULT_CONCORDANCE_PATH)?
+        }
+        None => Concordance::default(),
+    };
+
+    // Configurations to compare: --compare <a.json> <b.json>, or raw citations vs the defaults
+    let (mut config_a, mut config_b) = match args.iter().position(|arg| arg == "--compare") {
+        Some(idx) => match (args.get(idx + 1), args.get(idx + 2)) {
+            (Some(a), Some(b)) => (ParserConfig::load(a)?, ParserConfig::load(b)?),
+            _ => return Err("--compare expects two configuration files".into()),
+        },
+        None => (ParserConfig::raw(), ParserConfig::default()),
+    };
+    if config_a.name == config_b.name {
+        return Err(format!("both configurations are named '{}'", config_a.name).into());
+    }
+
+    // Normalisation rules switched off with --disable-rule-a / --disable-rule-b <name>
+    for rule in arg_values(&args, "--disable-rule-a") {
+        config_a.normalization.set(&rule, false)?;
+    }
+    for rule in arg_values(&args, "--disable-rule-b") {
+        config_b.normalization.set(&rule, false)?;
+    }
+
+    let input_file = arg_value(&args, "--input").unwrap_or_else(|| DEFAULT_INPUT_PATH.to_string());
+
+    let (stats_a, resolutions_a) = run_analysis(&input_file, &config_a, &reference, &overrides, &law_structure, &concordance)?;
+    let (stats_b, resolutions_b) = run_analysis(&input_file, &config_b, &reference, &overrides, &law_structure, &concordance)?;
 
     // Print comparison
-    print_comparison(&original_stats, &preprocessed_stats);
+    print_comparison(&stats_a, &stats_b);
+    write_comparison(&stats_a, &stats_b)?;
+
+    let changes = diff_resolutions(&resolutions_a, &resolutions_b);
+    let diff_path = format!("logs/config_diff_{}_vs_{}.jsonl", config_a.name, config_b.name);
+    write_changes(&changes, &diff_path)?;
 
     println!("\n✅ All done!\n");
     Ok(())
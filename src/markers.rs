@@ -0,0 +1,91 @@
+// Structure markers of citations per language
+//
+// Article, paragraph, letter and number markers and the "and following"
+// suffixes differ between the four national languages ("Art. 8 Abs. 2 lit. a",
+// "art. 8 al. 2 let. a", "art. 8 cpv. 2 lett. a", "art. 8 al. 2 lit. a"). The
+// regexes of article parsing, segmentation and unparseable classification are
+// built from this table, and every marker is a common word for abbreviation
+// extraction.
+use once_cell::sync::Lazy;
+
+pub struct LanguageMarkers {
+    pub article: &'static [&'static str],
+    pub paragraph: &'static [&'static str],
+    pub letter: &'static [&'static str],
+    pub number: &'static [&'static str],
+    /// "Art. 41 ff." and the like: open-ended range suffixes
+    pub following: &'static [&'static str],
+}
+
+pub const LANGUAGES: [LanguageMarkers; 4] = [
+    // German
+    LanguageMarkers {
+        article: &["art", "artikel"],
+        paragraph: &["abs", "absatz"],
+        letter: &["lit", "bst", "buchstabe"],
+        number: &["ziff", "ziffer", "nr"],
+        following: &["ff"],
+    },
+    // French
+    LanguageMarkers {
+        article: &["art", "article"],
+        paragraph: &["al", "alinéa", "par"],
+        letter: &["let", "lit", "lettre"],
+        number: &["ch", "chiffre", "no"],
+        following: &["ss", "sqq", "sq"],
+    },
+    // Italian
+    LanguageMarkers {
+        article: &["art", "articolo"],
+        paragraph: &["cpv", "capoverso"],
+        letter: &["lett", "lit", "lettera"],
+        number: &["n", "num", "cifra"],
+        following: &["segg", "seg", "ss"],
+    },
+    // Romansh
+    LanguageMarkers {
+        article: &["art", "artitgel"],
+        paragraph: &["al", "alinea"],
+        letter: &["lit", "litera"],
+        number: &["cif", "cifra"],
+        following: &["ss", "suandants"],
+    },
+];
+
+/// Regex alternation of the selected markers of every language, longest first
+/// so "segg" is tried before "seg"
+fn alternation(select: fn(&LanguageMarkers) -> &'static [&'static str]) -> String {
+    let mut markers: Vec<&str> = LANGUAGES.iter().flat_map(select).copied().collect();
+    markers.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    markers.dedup();
+    let escaped: Vec<String> = markers.iter().map(|marker| regex::escape(marker)).collect();
+    format!("(?:{})", escaped.join("|"))
+}
+
+/// "(?:artikel|articolo|...|art)", case-insensitive, followed by an optional dot
+pub static ARTICLE: Lazy<String> = Lazy::new(|| format!(r"(?i:{})\.?", alternation(|l| l.article)));
+
+/// Paragraph, letter and number markers: "Abs", "al", "cpv", "lett", "Ziff", "n", ...
+pub static SUBDIVISION: Lazy<String> = Lazy::new(|| {
+    format!(
+        "(?i:{}|{}|{})",
+        alternation(|l| l.paragraph),
+        alternation(|l| l.letter),
+        alternation(|l| l.number)
+    )
+});
+
+/// Open-range suffixes: "ff", "ss", "sqq", "segg", ...
+pub static FOLLOWING: Lazy<String> = Lazy::new(|| format!(r"(?i:{})\b", alternation(|l| l.following)));
+
+/// Every marker word, lowercase
+pub fn marker_words() -> impl Iterator<Item = &'static str> {
+    LANGUAGES.iter().flat_map(|l| {
+        l.article.iter()
+            .chain(l.paragraph)
+            .chain(l.letter)
+            .chain(l.number)
+            .chain(l.following)
+            .copied()
+    })
+}
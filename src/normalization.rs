@@ -0,0 +1,325 @@
+// Citation text normalisation applied before law and article extraction
+//
+// Port of the fixes previously done by preprocess_citations.py. Each rule can
+// be switched off individually to measure its impact.
+//
+// Besides rewriting single citations, the rules drop garbage that is not a
+// citation at all (dates, page references, proposals, cut-off sentences) and
+// complete article-only citations ("art. 128") with the law when exactly one
+// law is cited in the same element. Bare "Abs. 2"-style fragments are kept:
+// Phase 1.2 attaches them to the preceding citation.
+use crate::markers;
+use crate::tokenizer::{is_month, tokenize};
+use crate::unparseable::UnparseableReason;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// "43 aCP" -> "43 a CP" (digit + space + a + uppercase, mixed case like "BauR" allowed)
+static DIGIT_A_PREFIX_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d+)\s+a([A-ZÄÖÜ][A-ZÄÖÜa-zäöüß]{1,})\b").unwrap()
+});
+
+// " aBauR" -> " a BauR" (whitespace + a + uppercase)
+static A_PREFIX_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\s)a([A-ZÄÖÜ][A-ZÄÖÜa-zäöüß]{1,})\b").unwrap()
+});
+
+// "Art.6VwVG" -> "Art. 6 VwVG"
+static GLUED_ARTICLE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b([Aa]rt)\.?(\d+)([A-ZÄÖÜ][A-ZÄÖÜa-zäöü]{1,})\b").unwrap()
+});
+
+// "Loi sur l'administration3" -> "Loi sur l'administration"
+static FOOTNOTE_DIGITS_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"([a-zàâäéèêëïîôùûüÿœæç])\d+$").unwrap()
+});
+
+static ARTICLE_NUMBER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"\b{}\s*\d", *markers::ARTICLE)).unwrap()
+});
+
+// "207ff.", "218f."
+static PAGE_REFERENCE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d+\s*ff?\.?$").unwrap());
+
+// "... gemäss Abs", "... vgl. Art"
+static TRAILING_MARKER_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i:abs|art)\s*$").unwrap());
+
+// "RS 220", "SR 173.110"
+static RS_REFERENCE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:rs|sr)\s*(\d+(?:\.\d+)*)\b").unwrap()
+});
+
+// All caps ("ZGB", "OR") or mixed case with 2+ capitals ("VwVG", "SchKG")
+static ABBREV_SHAPE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[A-ZÄÖÜ]{2,}\b|\b[A-ZÄÖÜ][a-zäöü]*[A-ZÄÖÜ]").unwrap()
+});
+
+const INCOMPLETE_ENDINGS: [&str; 11] = [
+    "de la", "du", "des", "de l'", "della", "del", "ist (", "sind (", "sowie ", "et art", "e art",
+];
+
+const PROPOSAL_WORDS: [&str; 5] = ["proposition", "motion", "postulat", "initiative", "anfrage"];
+
+/// Longer than this without an article number: running text, not a citation
+const MAX_TEXT_WITHOUT_ARTICLE: usize = 50;
+
+/// Article-only citations are short: "art. 128", "Art. 41 Abs. 1"
+const MAX_ARTICLE_ONLY_LENGTH: usize = 25;
+
+/// Federal laws (lowercase) an article-only citation may be completed with
+const ENRICHMENT_LAWS: [&str; 28] = [
+    "stgb", "zgb", "or", "bv", "schkg", "zpo", "stpo", "uvg", "ahvg", "kvg", "bvg", "avig",
+    "urg", "dsg", "usg", "mwstg", "dbg", "vwvg", "pa", "cp", "cc", "cst", "cste", "cost",
+    "bgg", "svg", "emrk", "cedh",
+];
+
+/// Individually toggleable normalisation rules, all enabled by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NormalizationRules {
+    /// "43 aCP" -> "43 a CP"
+    pub split_digit_a_prefix: bool,
+    /// " aBauR" -> " a BauR"
+    pub split_a_prefix: bool,
+    /// "Art.6VwVG" -> "Art. 6 VwVG"
+    pub split_glued_article: bool,
+    /// "administration3" -> "administration"
+    pub strip_footnote_digits: bool,
+    /// Drop citations that are only digits ("125", "3.14")
+    pub drop_digit_only: bool,
+    /// Drop dates, page references, proposals and cut-off sentences
+    pub drop_garbage: bool,
+    /// "art. 128" -> "art. 128 ZGB" when ZGB is the only law of the element
+    pub enrich_article_only: bool,
+}
+
+impl Default for NormalizationRules {
+    fn default() -> Self {
+        NormalizationRules {
+            split_digit_a_prefix: true,
+            split_a_prefix: true,
+            split_glued_article: true,
+            strip_footnote_digits: true,
+            drop_digit_only: true,
+            drop_garbage: true,
+            enrich_article_only: true,
+        }
+    }
+}
+
+impl NormalizationRules {
+    pub const RULE_NAMES: [&'static str; 7] = [
+        "split_digit_a_prefix",
+        "split_a_prefix",
+        "split_glued_article",
+        "strip_footnote_digits",
+        "drop_digit_only",
+        "drop_garbage",
+        "enrich_article_only",
+    ];
+
+    /// All rules disabled: citations are used exactly as they appear in the dataset
+    pub fn none() -> Self {
+        NormalizationRules {
+            split_digit_a_prefix: false,
+            split_a_prefix: false,
+            split_glued_article: false,
+            strip_footnote_digits: false,
+            drop_digit_only: false,
+            drop_garbage: false,
+            enrich_article_only: false,
+        }
+    }
+
+    pub fn set(&mut self, rule: &str, enabled: bool) -> Result<(), String> {
+        let flag = match rule {
+            "split_digit_a_prefix" => &mut self.split_digit_a_prefix,
+            "split_a_prefix" => &mut self.split_a_prefix,
+            "split_glued_article" => &mut self.split_glued_article,
+            "strip_footnote_digits" => &mut self.strip_footnote_digits,
+            "drop_digit_only" => &mut self.drop_digit_only,
+            "drop_garbage" => &mut self.drop_garbage,
+            "enrich_article_only" => &mut self.enrich_article_only,
+            _ => {
+                return Err(format!(
+                    "unknown normalisation rule '{}' (expected one of: {})",
+                    rule,
+                    Self::RULE_NAMES.join(", ")
+                ))
+            }
+        };
+        *flag = enabled;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct NormalizedCitation {
+    pub text: String,
+    pub changed: bool,
+    /// Only digits remain: an article number without any law context
+    pub digit_only: bool,
+    /// Not a citation at all, see `garbage_reason`
+    pub garbage: Option<UnparseableReason>,
+}
+
+/// Apply the enabled rules in the same order as the former Python preprocessing
+pub fn normalize_citation(citation: &str, rules: &NormalizationRules) -> NormalizedCitation {
+    let mut text = citation.to_string();
+
+    if rules.split_digit_a_prefix {
+        text = DIGIT_A_PREFIX_PATTERN.replace_all(&text, "$1 a $2").to_string();
+    }
+    if rules.split_a_prefix {
+        text = A_PREFIX_PATTERN.replace_all(&text, "${1}a $2").to_string();
+    }
+    if rules.split_glued_article {
+        text = GLUED_ARTICLE_PATTERN.replace_all(&text, "$1. $2 $3").to_string();
+    }
+    if rules.strip_footnote_digits {
+        text = strip_footnote_digits(&text);
+    }
+
+    let compact: String = text.chars().filter(|c| *c != ' ' && *c != '.').collect();
+    let digit_only = rules.drop_digit_only
+        && !compact.is_empty()
+        && compact.chars().all(|c| c.is_ascii_digit());
+
+    let garbage = if rules.drop_garbage && !digit_only { garbage_reason(&text) } else { None };
+
+    NormalizedCitation {
+        changed: text != citation,
+        text,
+        digit_only,
+        garbage,
+    }
+}
+
+/// Why a normalised citation is not a citation at all, if it is garbage
+pub fn garbage_reason(citation: &str) -> Option<UnparseableReason> {
+    let citation_lower = citation.to_lowercase();
+    let has_article = ARTICLE_NUMBER_PATTERN.is_match(citation);
+
+    // Law titles like "loi du 14 décembre 1990" are fine when an article is cited
+    if !has_article && tokenize(citation).iter().any(|word| is_month(word)) {
+        return Some(UnparseableReason::Date);
+    }
+    if PAGE_REFERENCE_PATTERN.is_match(citation) {
+        return Some(UnparseableReason::PageReference);
+    }
+    if INCOMPLETE_ENDINGS.iter().any(|ending| citation.ends_with(ending))
+        || TRAILING_MARKER_PATTERN.is_match(citation)
+    {
+        return Some(UnparseableReason::Incomplete);
+    }
+    if PROPOSAL_WORDS.iter().any(|word| citation_lower.contains(word)) {
+        return Some(UnparseableReason::Proposal);
+    }
+    if !has_article && citation.chars().count() > MAX_TEXT_WITHOUT_ARTICLE {
+        return Some(UnparseableReason::NoArticle);
+    }
+    None
+}
+
+/// "RS 220" or the uppercase abbreviation of the law named in `citation`
+fn enrichment_law(citation: &str) -> Option<String> {
+    if let Some(caps) = RS_REFERENCE_PATTERN.captures(citation) {
+        return Some(format!("RS {}", &caps[1]));
+    }
+    let words = tokenize(citation);
+    ENRICHMENT_LAWS.iter()
+        .find(|law| words.iter().any(|word| word == *law))
+        .map(|law| law.to_uppercase())
+}
+
+/// "art. 128" with no law, RS number or abbreviation-shaped word; laws are
+/// whole words, so "cpv." and "para." are not "cp" and "pa"
+fn is_article_only(citation: &str) -> bool {
+    let words = tokenize(citation);
+    citation.trim().to_lowercase().starts_with("art")
+        && citation.chars().count() < MAX_ARTICLE_ONLY_LENGTH
+        && !words.iter().any(|word| ENRICHMENT_LAWS.contains(&word.as_str()))
+        && !RS_REFERENCE_PATTERN.is_match(citation)
+        && !ABBREV_SHAPE_PATTERN.is_match(citation)
+}
+
+/// The only law cited among the element's `citations`, to complete its
+/// article-only citations with; `None` when no law or several are cited
+pub fn sole_element_law<'a>(citations: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut laws = citations.into_iter().filter_map(enrichment_law);
+    let law = laws.next()?;
+    laws.all(|other| other == law).then_some(law)
+}
+
+/// "art. 128" -> "art. 128 ZGB"; `None` when the citation is not article-only
+pub fn enrich_article_only(citation: &str, law: &str) -> Option<String> {
+    is_article_only(citation).then(|| format!("{} {}", citation, law))
+}
+
+/// Strip trailing footnote numbers from a law title ("administration3")
+pub fn strip_footnote_digits(text: &str) -> String {
+    FOOTNOTE_DIGITS_PATTERN.replace_all(text, "$1").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_citations() {
+        let rules = NormalizationRules::default();
+        let cases = [
+            ("Art. 43 aCP", "Art. 43 a CP"),
+            ("Art. 12 aBauR", "Art. 12 a BauR"),
+            ("Art.6VwVG", "Art. 6 VwVG"),
+            ("Loi sur l'administration3", "Loi sur l'administration"),
+            ("Art. 8 BV", "Art. 8 BV"),
+        ];
+        for (citation, expected) in cases {
+            let normalized = normalize_citation(citation, &rules);
+            assert_eq!(normalized.text, expected, "{}", citation);
+            assert_eq!(normalized.changed, citation != expected, "{}", citation);
+        }
+        assert_eq!(normalize_citation("Art.6VwVG", &NormalizationRules::none()).text, "Art.6VwVG");
+    }
+
+    #[test]
+    fn flags_digit_only_and_garbage() {
+        let rules = NormalizationRules::default();
+        assert!(normalize_citation("125", &rules).digit_only);
+        assert!(normalize_citation("3.14", &rules).digit_only);
+        let cases = [
+            ("loi du 14 décembre 1990", Some(UnparseableReason::Date)),
+            ("Art. 2 loi du 14 décembre 1990", None),
+            ("art. 5 al. 1 du domaine public", None),
+            ("207ff.", Some(UnparseableReason::PageReference)),
+            ("Art. 8 BV sowie ", Some(UnparseableReason::Incomplete)),
+            ("gemäss Abs", Some(UnparseableReason::Incomplete)),
+            ("Motion Müller", Some(UnparseableReason::Proposal)),
+            ("Art. 8 BV", None),
+            ("Abs. 2", None),
+        ];
+        for (citation, expected) in cases {
+            assert_eq!(normalize_citation(citation, &rules).garbage, expected, "{}", citation);
+        }
+    }
+
+    #[test]
+    fn completes_article_only_citations() {
+        assert_eq!(sole_element_law(["Art. 8 ZGB", "art. 128"]), Some("ZGB".to_string()));
+        assert_eq!(sole_element_law(["Art. 8 ZGB", "Art. 2 ZGB"]), Some("ZGB".to_string()));
+        assert_eq!(sole_element_law(["Art. 1 SR 173.110"]), Some("RS 173.110".to_string()));
+        assert_eq!(sole_element_law(["Art. 8 ZGB", "Art. 41 OR"]), None);
+        assert_eq!(sole_element_law(["art. 128"]), None);
+
+        assert_eq!(enrich_article_only("art. 128", "ZGB"), Some("art. 128 ZGB".to_string()));
+        assert_eq!(enrich_article_only("Art. 41 Abs. 1", "OR"), Some("Art. 41 Abs. 1 OR".to_string()));
+        assert_eq!(enrich_article_only("art. 5 cpv. 2", "CC"), Some("art. 5 cpv. 2 CC".to_string()));
+        assert_eq!(enrich_article_only("art. 5 para. 2", "CO"), Some("art. 5 para. 2 CO".to_string()));
+        assert_eq!(enrich_article_only("art. 5 CPV", "CO"), None);
+        assert_eq!(enrich_article_only("art. 5 pa", "CO"), None);
+        assert_eq!(enrich_article_only("Art. 8 ZGB", "ZGB"), None);
+        assert_eq!(enrich_article_only("Art. 12 BauG", "ZGB"), None);
+    }
+}
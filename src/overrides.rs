@@ -0,0 +1,149 @@
+// User-supplied overrides consulted before any citation heuristic
+//
+// The overrides file is a JSON list. Each entry matches either an exact
+// citation string or a regex, and maps it to a dotted RS number, a CANTONAL_
+// or FOREIGN_ key, or "ignore"; any other target is an error:
+//
+//   [
+//     {"citation": "Art. 34 BauG", "target": "CANTONAL_BAUG_BE"},
+//     {"regex": "(?i)\\bWUB\\b", "target": "742.101"},
+//     {"regex": "^(?:Abs|al)\\.\\s*\\d+$", "target": "ignore"}
+//   ]
+//
+// Entries are tried in file order; the first match wins.
+use crate::constitutions::FOREIGN_PREFIX;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::fs::File;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// "220", "173.110.2", "CANTONAL_BAUG_BE", "FOREIGN_CST_DE"
+static TARGET_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"^(?:\d+(?:\.\d+)*|(?:CANTONAL_|{})[\p{{Lu}}\d_]+)$", FOREIGN_PREFIX)).unwrap()
+});
+
+#[derive(Debug, Deserialize)]
+struct OverrideEntry {
+    citation: Option<String>,
+    regex: Option<String>,
+    target: String,
+}
+
+#[derive(Debug)]
+enum OverrideMatcher {
+    Exact(String),
+    Pattern(Regex),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideTarget {
+    /// RS number, CANTONAL_ or FOREIGN_ key to group the citation under
+    Law(String),
+    /// Drop the citation entirely
+    Ignore,
+}
+
+#[derive(Debug)]
+struct CitationOverride {
+    matcher: OverrideMatcher,
+    target: OverrideTarget,
+    hits: AtomicUsize,
+}
+
+impl CitationOverride {
+    fn label(&self) -> String {
+        match &self.matcher {
+            OverrideMatcher::Exact(citation) => format!("\"{}\"", citation),
+            OverrideMatcher::Pattern(re) => format!("/{}/", re.as_str()),
+        }
+    }
+
+    fn matches(&self, citation: &str) -> bool {
+        match &self.matcher {
+            OverrideMatcher::Exact(exact) => citation.trim() == exact,
+            OverrideMatcher::Pattern(re) => re.is_match(citation),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Overrides {
+    entries: Vec<CitationOverride>,
+}
+
+impl Overrides {
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        println!("Loading citation overrides from {}...", path);
+        let file = File::open(path)?;
+        let raw: Vec<OverrideEntry> = serde_json::from_reader(file)?;
+
+        let mut entries = Vec::with_capacity(raw.len());
+        for (idx, entry) in raw.into_iter().enumerate() {
+            let matcher = match (entry.citation, entry.regex) {
+                (Some(citation), None) => OverrideMatcher::Exact(citation.trim().to_string()),
+                (None, Some(pattern)) => OverrideMatcher::Pattern(Regex::new(&pattern)
+                    .map_err(|e| format!("override #{}: invalid regex: {}", idx + 1, e))?),
+                _ => {
+                    return Err(format!(
+                        "override #{}: exactly one of \"citation\" or \"regex\" is required",
+                        idx + 1
+                    ).into())
+                }
+            };
+
+            let target = entry.target.trim();
+            if target.is_empty() {
+                return Err(format!("override #{}: empty target", idx + 1).into());
+            }
+            let target = if target.eq_ignore_ascii_case("ignore") {
+                OverrideTarget::Ignore
+            } else if TARGET_PATTERN.is_match(target) {
+                OverrideTarget::Law(target.to_string())
+            } else {
+                return Err(format!(
+                    "override #{}: target '{}' is not an RS number, a CANTONAL_/FOREIGN_ key or \"ignore\"",
+                    idx + 1, target
+                ).into());
+            };
+
+            entries.push(CitationOverride {
+                matcher,
+                target,
+                hits: AtomicUsize::new(0),
+            });
+        }
+
+        println!("  ✓ Loaded {} overrides", entries.len());
+        Ok(Overrides { entries })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the target of the first override matching `citation`, counting the hit
+    pub fn lookup(&self, citation: &str) -> Option<&OverrideTarget> {
+        let entry = self.entries.iter().find(|entry| entry.matches(citation))?;
+        entry.hits.fetch_add(1, Ordering::Relaxed);
+        Some(&entry.target)
+    }
+
+    /// Print hit counts per override and reset them for the next run
+    pub fn report_and_reset_hits(&self) {
+        if self.is_empty() {
+            return;
+        }
+
+        println!("\n🎛️  Override hits:");
+        for entry in &self.entries {
+            let hits = entry.hits.swap(0, Ordering::Relaxed);
+            let target = match &entry.target {
+                OverrideTarget::Law(law) => law.as_str(),
+                OverrideTarget::Ignore => "ignore",
+            };
+            let marker = if hits == 0 { "⚠" } else { "✓" };
+            println!("  {} {:>6}  {} → {}", marker, hits, entry.label(), target);
+        }
+    }
+}
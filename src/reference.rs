@@ -0,0 +1,294 @@
+// Reference data: abbreviation triplets, aliases and law titles, loaded and checked once
+//
+// abbreviation_triplets.json maps each RS number to its abbreviation per
+// language:
+//
+//   {"101": {"FR": "Cst.", "DE": "BV", "IT": "Cost."}}
+//
+// titles_mapping.json holds the titles both ways:
+//
+//   {"title_to_rs": {"constitution fédérale ... du 18 avril 1999": "101"},
+//    "titles_by_rs": {"101": {"FR": "Constitution fédérale ...", "DE": "Bundesverfassung ..."}}}
+//
+// abbreviation_aliases.json (optional) adds further abbreviations per law,
+// optionally limited to the decisions of a period:
+//
+//   [{"rs_number": "220", "aliases": ["OR", "CO"]},
+//    {"rs_number": "173.110@former", "aliases": ["OG", "OJ"], "valid_until": "2006-12-31"}]
+//
+// A valid alias takes precedence over the triplets; an alias outside its
+// period leaves the lookup to the triplets. An "@former" RS number names the
+// former version of the law, here the act the current 173.110 (BGG) replaced. Aliases are normalised like the
+// triplets ("O.R." is "or"), so an alias repeating another one of the same
+// law and period after normalisation is reported and skipped.
+//
+// learned_stopwords.txt (optional, reviewed output of `mine-stopwords`) lists
+// one word per line, `#` starting a comment; the words are never taken for
+// law abbreviations, like the built-in common words.
+//
+// Unreadable or malformed files are errors. Inconsistencies (an abbreviation
+// or title claimed by two laws, a title pointing at an unknown RS number) are
+// reported as warnings; the first RS number in sorted order wins.
+use crate::constitutions::index_cantonal_constitutions;
+use crate::title_index::TitleIndex;
+use crate::versions::{base_law, parse_decision_date};
+use crate::{normalize_abbreviation, AHashMap, AHashSet, AbbrevToRs};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+
+pub const DEFAULT_TRIPLETS_PATH: &str = "abbreviation_triplets.json";
+pub const DEFAULT_TITLES_PATH: &str = "titles_mapping.json";
+pub const DEFAULT_ALIASES_PATH: &str = "abbreviation_aliases.json";
+pub const DEFAULT_STOPWORDS_PATH: &str = "learned_stopwords.txt";
+
+/// Warnings printed in full; the rest are only counted
+const MAX_PRINTED_WARNINGS: usize = 10;
+
+/// RS number -> language -> abbreviation or title
+pub type ByLanguage = BTreeMap<String, BTreeMap<String, String>>;
+
+#[derive(Debug, Deserialize)]
+struct TitlesMapping {
+    title_to_rs: HashMap<String, String>,
+    titles_by_rs: ByLanguage,
+}
+
+#[derive(Debug, Deserialize)]
+struct AliasEntry {
+    rs_number: String,
+    aliases: Vec<String>,
+    valid_from: Option<String>,
+    valid_until: Option<String>,
+}
+
+/// One alias of a law, with the decision dates it applies to (ISO, inclusive)
+#[derive(Debug, Clone)]
+pub struct AbbreviationAlias {
+    pub rs_number: String,
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+}
+
+impl AbbreviationAlias {
+    /// Whether the alias applies to a decision of `date`; always when the date is unknown
+    fn applies(&self, date: Option<&str>) -> bool {
+        let Some(date) = date else { return true };
+        self.valid_from.as_deref().is_none_or(|from| date >= from)
+            && self.valid_until.as_deref().is_none_or(|until| date <= until)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ReferenceData {
+    pub triplets: ByLanguage,
+    /// Normalised abbreviation -> RS number
+    pub abbrev_to_rs: AbbrevToRs,
+    /// Normalised alias -> laws it stands for, by period
+    pub aliases: AHashMap<String, Vec<AbbreviationAlias>>,
+    pub title_to_rs: HashMap<String, String>,
+    pub titles_by_rs: ByLanguage,
+    /// Index over every title of both maps; `None` without a titles file
+    pub title_index: Option<TitleIndex>,
+    /// Canton code -> RS number of the cantonal constitution
+    pub cantonal_constitutions: AHashMap<&'static str, String>,
+    /// Lowercase words that are not abbreviations, besides the built-in ones
+    pub learned_stopwords: AHashSet<String>,
+    pub warnings: Vec<String>,
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(serde_json::from_reader(std::io::BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))?)
+}
+
+/// Error for blank RS numbers or values, which would silently match everything
+fn check_entries(path: &str, entries: &ByLanguage) -> Result<(), Box<dyn std::error::Error>> {
+    for (rs_number, values) in entries {
+        if rs_number.trim().is_empty() {
+            return Err(format!("{}: empty RS number", path).into());
+        }
+        if let Some((language, _)) = values.iter().find(|(_, value)| value.trim().is_empty()) {
+            return Err(format!("{}: empty {} entry for RS {}", path, language, rs_number).into());
+        }
+    }
+    Ok(())
+}
+
+impl ReferenceData {
+    /// Triplets are required; the titles file is optional (title matching is
+    /// then unavailable) but must be valid when present, as must the aliases
+    pub fn load(triplets_path: &str, titles_path: &str, aliases_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut reference = ReferenceData::default();
+
+        println!("Loading abbreviation triplets from {}...", triplets_path);
+        reference.triplets = read_json(triplets_path)?;
+        check_entries(triplets_path, &reference.triplets)?;
+        for (rs_number, languages) in &reference.triplets {
+            for abbrev in languages.values() {
+                let normalized = normalize_abbreviation(abbrev);
+                match reference.abbrev_to_rs.get(&normalized) {
+                    Some(existing) if existing != rs_number => reference.warnings.push(format!(
+                        "abbreviation '{}' of RS {} already maps to RS {}", abbrev, rs_number, existing
+                    )),
+                    Some(_) => {}
+                    None => {
+                        reference.abbrev_to_rs.insert(normalized, rs_number.clone());
+                    }
+                }
+            }
+        }
+        println!("  ✓ Loaded {} RS numbers", reference.triplets.len());
+        println!("  ✓ Mapped {} abbreviations", reference.abbrev_to_rs.len());
+
+        if std::path::Path::new(titles_path).exists() {
+            println!("Loading law titles from {}...", titles_path);
+            let mapping: TitlesMapping = read_json(titles_path)?;
+            check_entries(titles_path, &mapping.titles_by_rs)?;
+            if let Some(title) = mapping.title_to_rs.iter().find(|(_, rs)| rs.trim().is_empty()).map(|(title, _)| title) {
+                return Err(format!("{}: empty RS number for title '{}'", titles_path, title).into());
+            }
+            reference.title_to_rs = mapping.title_to_rs;
+            reference.titles_by_rs = mapping.titles_by_rs;
+            reference.check_titles();
+            println!("  ✓ Loaded {} titles of {} RS numbers",
+                     reference.title_to_rs.len(),
+                     reference.titles_by_rs.len());
+            reference.title_index = Some(TitleIndex::build(&reference.titles()));
+            reference.cantonal_constitutions = index_cantonal_constitutions(&reference.titles_by_rs);
+            println!("  ✓ Found the constitutions of {} cantons", reference.cantonal_constitutions.len());
+        } else {
+            println!("  ⚠ {} not found, title matching disabled", titles_path);
+        }
+
+        if std::path::Path::new(aliases_path).exists() {
+            reference.load_aliases(aliases_path)?;
+        }
+
+        reference.print_warnings();
+        Ok(reference)
+    }
+
+    fn load_aliases(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        println!("Loading abbreviation aliases from {}...", path);
+        let entries: Vec<AliasEntry> = read_json(path)?;
+
+        let date = |value: Option<String>, idx: usize| -> Result<Option<String>, String> {
+            value.map(|value| parse_decision_date(&value)
+                .ok_or_else(|| format!("{}: alias entry #{}: invalid date '{}'", path, idx + 1, value)))
+                .transpose()
+        };
+        let mut count = 0;
+        // (normalised alias, RS number, period) -> alias as written
+        let mut seen: AHashMap<(String, String, Option<String>, Option<String>), String> = AHashMap::new();
+        for (idx, entry) in entries.into_iter().enumerate() {
+            if entry.rs_number.trim().is_empty() || entry.aliases.iter().any(|alias| alias.trim().is_empty()) {
+                return Err(format!("{}: alias entry #{}: empty RS number or alias", path, idx + 1).into());
+            }
+            let valid_from = date(entry.valid_from, idx)?;
+            let valid_until = date(entry.valid_until, idx)?;
+            if let (Some(from), Some(until)) = (&valid_from, &valid_until) {
+                if from > until {
+                    return Err(format!("{}: alias entry #{}: valid_from {} is after valid_until {}", path, idx + 1, from, until).into());
+                }
+            }
+            let law = base_law(&entry.rs_number);
+            if !self.triplets.contains_key(law) && !self.titles_by_rs.contains_key(law) {
+                self.warnings.push(format!("aliases of RS {} point at an RS number without triplets or titles", entry.rs_number));
+            }
+
+            for alias in &entry.aliases {
+                let normalized = normalize_abbreviation(alias);
+                let key = (normalized.clone(), entry.rs_number.clone(), valid_from.clone(), valid_until.clone());
+                if let Some(first) = seen.insert(key, alias.clone()) {
+                    self.warnings.push(format!("alias '{}' of RS {} duplicates '{}' after normalisation", alias, entry.rs_number, first));
+                    continue;
+                }
+                self.aliases.entry(normalized).or_default().push(AbbreviationAlias {
+                    rs_number: entry.rs_number.clone(),
+                    valid_from: valid_from.clone(),
+                    valid_until: valid_until.clone(),
+                });
+                count += 1;
+            }
+        }
+        println!("  ✓ Loaded {} aliases", count);
+        Ok(())
+    }
+
+    /// Add the words of a learned stopword file
+    pub fn load_stopwords(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        println!("Loading learned stopwords from {}...", path);
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let before = self.learned_stopwords.len();
+        for line in text.lines() {
+            let word = line.split('#').next().unwrap_or_default().trim();
+            if !word.is_empty() {
+                self.learned_stopwords.insert(word.to_lowercase());
+            }
+        }
+        println!("  ✓ Loaded {} stopwords", self.learned_stopwords.len() - before);
+        Ok(())
+    }
+
+    /// RS number of an abbreviation cited in a decision of `date`: a valid alias
+    /// first, then the triplets
+    pub fn lookup_abbreviation(&self, abbrev: &str, date: Option<&str>) -> Option<&str> {
+        let normalized = normalize_abbreviation(abbrev);
+        self.aliases.get(&normalized)
+            .and_then(|aliases| aliases.iter().find(|alias| alias.applies(date)))
+            .map(|alias| alias.rs_number.as_str())
+            .or_else(|| self.abbrev_to_rs.get(&normalized).map(String::as_str))
+    }
+
+    /// Titles of `title_to_rs` pointing at an RS number without `titles_by_rs`
+    /// entry, and titles listed under two RS numbers
+    fn check_titles(&mut self) {
+        let mut sorted: Vec<(&String, &String)> = self.title_to_rs.iter().collect();
+        sorted.sort();
+        for (title, rs_number) in sorted {
+            if !self.titles_by_rs.contains_key(rs_number) {
+                self.warnings.push(format!("title '{}' maps to RS {} which has no titles_by_rs entry", title, rs_number));
+            }
+        }
+
+        let mut owners: HashMap<&str, &str> = HashMap::new();
+        for (rs_number, titles) in &self.titles_by_rs {
+            for title in titles.values() {
+                match owners.get(title.as_str()) {
+                    Some(owner) if owner != rs_number => self.warnings.push(format!(
+                        "title '{}' of RS {} is also a title of RS {}", title, rs_number, owner
+                    )),
+                    Some(_) => {}
+                    None => {
+                        owners.insert(title, rs_number);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Every (title, RS number) pair of both maps
+    fn titles(&self) -> HashMap<String, String> {
+        let mut titles = self.title_to_rs.clone();
+        for (rs_number, by_language) in &self.titles_by_rs {
+            for title in by_language.values() {
+                titles.entry(title.clone()).or_insert_with(|| rs_number.clone());
+            }
+        }
+        titles
+    }
+
+    fn print_warnings(&self) {
+        if self.warnings.is_empty() {
+            return;
+        }
+        println!("  ⚠ {} reference data warnings:", self.warnings.len());
+        for warning in self.warnings.iter().take(MAX_PRINTED_WARNINGS) {
+            println!("    - {}", warning);
+        }
+        if self.warnings.len() > MAX_PRINTED_WARNINGS {
+            println!("    ... and {} more", self.warnings.len() - MAX_PRINTED_WARNINGS);
+        }
+    }
+}
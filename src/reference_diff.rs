@@ -0,0 +1,209 @@
+// `diff-reference` subcommand: what changed between two versions of the
+// reference data, and how that changes citation resolution
+//
+//   cartesian-law-analysis diff-reference <old_dir> <new_dir> [--input <dataset>] [--sample <elements>]
+//
+// Each directory holds an abbreviation_triplets.json and a titles_mapping.json
+// (and optionally an abbreviation_aliases.json).
+// Added, removed and changed RS numbers, abbreviations (as normalised for
+// lookup, so a remapping is exactly a change of `normalize_to_rs_number`),
+// aliases and titles go to logs/reference_diff.jsonl. Alias periods are
+// written "2007-01-01..", "..2006-12-31" or ".." (always valid). Phase 1 is then run on the first
+// elements of the dataset with both versions, and the citations resolving
+// differently go to logs/reference_diff_impact.jsonl.
+use crate::config::ParserConfig;
+use crate::config_diff::{collect_resolutions, diff_resolutions, write_changes};
+use crate::law_structure::LawStructure;
+use crate::overrides::Overrides;
+use crate::reference::{AbbreviationAlias, ReferenceData, DEFAULT_ALIASES_PATH, DEFAULT_TITLES_PATH, DEFAULT_TRIPLETS_PATH};
+use crate::{arg_value, group_citations_by_law, load_and_filter_dataset, DEFAULT_INPUT_PATH};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const DIFF_PATH: &str = "logs/reference_diff.jsonl";
+const IMPACT_PATH: &str = "logs/reference_diff_impact.jsonl";
+
+/// Elements re-resolved when --sample is not given
+const DEFAULT_SAMPLE: usize = 10_000;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ReferenceChange {
+    RsAdded { rs_number: String },
+    RsRemoved { rs_number: String },
+    AbbreviationAdded { abbreviation: String, rs_number: String },
+    AbbreviationRemoved { abbreviation: String, rs_number: String },
+    AbbreviationRemapped { abbreviation: String, old_rs: String, new_rs: String },
+    AliasAdded { alias: String, rs_number: String, periods: Vec<String> },
+    AliasRemoved { alias: String, rs_number: String, periods: Vec<String> },
+    AliasPeriodChanged { alias: String, rs_number: String, old_periods: Vec<String>, new_periods: Vec<String> },
+    TitleAdded { rs_number: String, language: String, title: String },
+    TitleRemoved { rs_number: String, language: String, title: String },
+    TitleChanged { rs_number: String, language: String, old_title: String, new_title: String },
+}
+
+impl ReferenceChange {
+    fn label(&self) -> &'static str {
+        match self {
+            ReferenceChange::RsAdded { .. } => "RS numbers added",
+            ReferenceChange::RsRemoved { .. } => "RS numbers removed",
+            ReferenceChange::AbbreviationAdded { .. } => "abbreviations added",
+            ReferenceChange::AbbreviationRemoved { .. } => "abbreviations removed",
+            ReferenceChange::AbbreviationRemapped { .. } => "abbreviations remapped",
+            ReferenceChange::AliasAdded { .. } => "aliases added",
+            ReferenceChange::AliasRemoved { .. } => "aliases removed",
+            ReferenceChange::AliasPeriodChanged { .. } => "alias periods changed",
+            ReferenceChange::TitleAdded { .. } => "titles added",
+            ReferenceChange::TitleRemoved { .. } => "titles removed",
+            ReferenceChange::TitleChanged { .. } => "titles changed",
+        }
+    }
+}
+
+fn load_version(dir: &str) -> Result<ReferenceData, Box<dyn std::error::Error>> {
+    let path = |file: &str| Path::new(dir).join(file).to_string_lossy().into_owned();
+    ReferenceData::load(&path(DEFAULT_TRIPLETS_PATH), &path(DEFAULT_TITLES_PATH), &path(DEFAULT_ALIASES_PATH))
+}
+
+fn rs_numbers(reference: &ReferenceData) -> BTreeSet<&String> {
+    reference.triplets.keys().chain(reference.titles_by_rs.keys()).collect()
+}
+
+/// (normalised alias, RS number) -> sorted validity periods
+fn alias_periods(reference: &ReferenceData) -> BTreeMap<(String, String), Vec<String>> {
+    let mut periods: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    for (alias, entries) in &reference.aliases {
+        for AbbreviationAlias { rs_number, valid_from, valid_until } in entries {
+            periods.entry((alias.clone(), rs_number.clone())).or_default().push(format!(
+                "{}..{}",
+                valid_from.as_deref().unwrap_or_default(),
+                valid_until.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+    for list in periods.values_mut() {
+        list.sort();
+    }
+    periods
+}
+
+fn diff_references(old: &ReferenceData, new: &ReferenceData) -> Vec<ReferenceChange> {
+    let mut changes = Vec::new();
+
+    let (old_rs, new_rs) = (rs_numbers(old), rs_numbers(new));
+    for rs_number in new_rs.difference(&old_rs) {
+        changes.push(ReferenceChange::RsAdded { rs_number: rs_number.to_string() });
+    }
+    for rs_number in old_rs.difference(&new_rs) {
+        changes.push(ReferenceChange::RsRemoved { rs_number: rs_number.to_string() });
+    }
+
+    let abbreviations: BTreeSet<&String> = old.abbrev_to_rs.keys().chain(new.abbrev_to_rs.keys()).collect();
+    for abbreviation in abbreviations {
+        let abbreviation = abbreviation.clone();
+        match (old.abbrev_to_rs.get(&abbreviation), new.abbrev_to_rs.get(&abbreviation)) {
+            (None, Some(rs_number)) => changes.push(ReferenceChange::AbbreviationAdded { abbreviation, rs_number: rs_number.clone() }),
+            (Some(rs_number), None) => changes.push(ReferenceChange::AbbreviationRemoved { abbreviation, rs_number: rs_number.clone() }),
+            (Some(old_rs), Some(new_rs)) if old_rs != new_rs => changes.push(ReferenceChange::AbbreviationRemapped {
+                abbreviation,
+                old_rs: old_rs.clone(),
+                new_rs: new_rs.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    let (old_aliases, new_aliases) = (alias_periods(old), alias_periods(new));
+    let alias_keys: BTreeSet<&(String, String)> = old_aliases.keys().chain(new_aliases.keys()).collect();
+    for key in alias_keys {
+        let (alias, rs_number) = (key.0.clone(), key.1.clone());
+        match (old_aliases.get(key), new_aliases.get(key)) {
+            (None, Some(periods)) => changes.push(ReferenceChange::AliasAdded { alias, rs_number, periods: periods.clone() }),
+            (Some(periods), None) => changes.push(ReferenceChange::AliasRemoved { alias, rs_number, periods: periods.clone() }),
+            (Some(old_periods), Some(new_periods)) if old_periods != new_periods => changes.push(ReferenceChange::AliasPeriodChanged {
+                alias,
+                rs_number,
+                old_periods: old_periods.clone(),
+                new_periods: new_periods.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    let empty = BTreeMap::new();
+    let titled: BTreeSet<&String> = old.titles_by_rs.keys().chain(new.titles_by_rs.keys()).collect();
+    for rs_number in titled {
+        let old_titles = old.titles_by_rs.get(rs_number).unwrap_or(&empty);
+        let new_titles = new.titles_by_rs.get(rs_number).unwrap_or(&empty);
+        let languages: BTreeSet<&String> = old_titles.keys().chain(new_titles.keys()).collect();
+        for language in languages {
+            let (rs_number, language_name) = (rs_number.clone(), language.clone());
+            match (old_titles.get(language), new_titles.get(language)) {
+                (None, Some(title)) => changes.push(ReferenceChange::TitleAdded { rs_number, language: language_name, title: title.clone() }),
+                (Some(title), None) => changes.push(ReferenceChange::TitleRemoved { rs_number, language: language_name, title: title.clone() }),
+                (Some(old_title), Some(new_title)) if old_title != new_title => changes.push(ReferenceChange::TitleChanged {
+                    rs_number,
+                    language: language_name,
+                    old_title: old_title.clone(),
+                    new_title: new_title.clone(),
+                }),
+                _ => {}
+            }
+        }
+    }
+    changes
+}
+
+/// Entry point of `diff-reference`; `args` follow the subcommand name
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some(old_dir), Some(new_dir)) = (args.first(), args.get(1)) else {
+        return Err("usage: diff-reference <old_dir> <new_dir> [--input <dataset>] [--sample <elements>]".into());
+    };
+    let input_file = arg_value(args, "--input").unwrap_or_else(|| DEFAULT_INPUT_PATH.to_string());
+    let sample = match arg_value(args, "--sample") {
+        Some(value) => value.parse::<usize>().map_err(|e| format!("--sample {}: {}", value, e))?,
+        None => DEFAULT_SAMPLE,
+    };
+
+    println!("\n🔎 Comparing reference data {} -> {}...", old_dir, new_dir);
+    let old = load_version(old_dir)?;
+    let new = load_version(new_dir)?;
+
+    std::fs::create_dir_all("logs")?;
+    let changes = diff_references(&old, &new);
+    let mut writer = BufWriter::new(File::create(DIFF_PATH)?);
+    for change in &changes {
+        writeln!(writer, "{}", serde_json::to_string(change)?)?;
+    }
+    writer.flush()?;
+
+    let mut per_kind: BTreeMap<&str, usize> = BTreeMap::new();
+    for change in &changes {
+        *per_kind.entry(change.label()).or_default() += 1;
+    }
+    println!("\n📚 Reference data changes: {}", changes.len());
+    for (label, count) in &per_kind {
+        println!("  • {:<24} {:>8}", label, count);
+    }
+    println!("  ✓ Saved to {}", DIFF_PATH);
+
+    // Re-resolve a sample of citations with both versions
+    let mut elements = load_and_filter_dataset(&input_file)?;
+    elements.truncate(sample);
+    println!("\n🧪 Re-resolving the citations of {} elements with both versions...", elements.len());
+    let config = ParserConfig::default();
+    let (overrides, law_structure) = (Overrides::default(), LawStructure::default());
+    let (old_groups, old_unparseable, _) = group_citations_by_law(&elements, &old, &overrides, &law_structure, &config);
+    let (new_groups, new_unparseable, _) = group_citations_by_law(&elements, &new, &overrides, &law_structure, &config);
+    let impact = diff_resolutions(
+        &collect_resolutions(&old_groups, &old_unparseable),
+        &collect_resolutions(&new_groups, &new_unparseable),
+    );
+    write_changes(&impact, IMPACT_PATH)?;
+
+    println!("\n✅ All done!\n");
+    Ok(())
+}
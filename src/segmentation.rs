@@ -0,0 +1,233 @@
+// Splitting of citations that reference several laws
+//
+// "Art. 8 BV und Art. 6 EMRK" or "BV Art. 8 i.V.m. EMRK Art. 14" name one law
+// per article group. A law mention after unclaimed articles closes their group
+// (article first), any other law mention opens a group with the articles that
+// follow (law first), so both orders can mix: "Art. 8 BV und EMRK Art. 14".
+// Connectors between segments are dropped. "Art. 8 und 9 BV" stays a single
+// segment.
+use crate::constitutions::names_constitution;
+use crate::reference::ReferenceData;
+use crate::{extract_law_abbreviation, markers, normalize_to_rs_number, AbbrevSource, RS_PATTERN, SR_PATTERN};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static ARTICLE_START_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"\b{}\s*\d", *markers::ARTICLE)).unwrap()
+});
+
+static WORD_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b\p{L}[\p{L}.]*").unwrap()
+});
+
+// At least two capitals: "BauG", "KVG", "SchKG" (cantonal laws absent from the triplets)
+static ABBREV_SHAPE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[A-ZÄÖÜ][a-zäöü]*[A-ZÄÖÜ]").unwrap()
+});
+
+// "lit.", "let", "Ziff." right before a word: "lit. e" is a letter, not the connector "e"
+static SUBDIVISION_END_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"\b{}\.?$", *markers::SUBDIVISION)).unwrap()
+});
+
+/// Words linking two law references, longest first so "en relation avec" wins over "et"
+const CONNECTORS: [&str; 17] = [
+    "in verbindung mit",
+    "en relation avec",
+    "in relazione con",
+    "in combinazione con",
+    "en lien avec",
+    "combiné avec",
+    "i.v.m.",
+    "i.v.m",
+    "sowie",
+    "bzw.",
+    "resp.",
+    "und",
+    "oder",
+    "et",
+    "ou",
+    "e",
+    "o",
+];
+
+fn has_law_mention(text: &str, reference: &ReferenceData) -> bool {
+    // A lone word is never in law position, so constitutions are checked apart
+    if names_constitution(text) {
+        return true;
+    }
+    let Some(extraction) = extract_law_abbreviation(text, reference) else { return false };
+    match extraction.best.source {
+        AbbrevSource::RsNumber | AbbrevSource::Constitution => true,
+        _ => extraction.candidates().any(|candidate| {
+            normalize_to_rs_number(&candidate.abbrev, reference, None).is_some()
+                || ABBREV_SHAPE_PATTERN.is_match(&candidate.abbrev)
+        }),
+    }
+}
+
+/// Strip trailing punctuation and connector words ("Art. 8 BV und" -> "Art. 8 BV").
+/// The one-letter connectors "e" and "o" are kept after a subdivision marker
+/// ("Art. 5 lit. e").
+fn trim_connectors(segment: &str) -> &str {
+    let is_separator = |c: char| c.is_whitespace() || c == ',' || c == ';';
+    let mut trimmed = segment.trim_end_matches(is_separator);
+    loop {
+        let connector_start = CONNECTORS.iter().find_map(|connector| {
+            let split = trimmed.len().checked_sub(connector.len())?;
+            let matches = trimmed.is_char_boundary(split)
+                && trimmed[split..].to_lowercase() == *connector
+                && trimmed[..split].ends_with(is_separator)
+                && (connector.len() > 1 || !SUBDIVISION_END_PATTERN.is_match(trimmed[..split].trim_end()));
+            matches.then_some(split)
+        });
+        match connector_start {
+            Some(split) => trimmed = trimmed[..split].trim_end_matches(is_separator),
+            None => return trimmed,
+        }
+    }
+}
+
+/// Byte spans of the law mentions in a citation, in order
+fn law_mentions(citation: &str, reference: &ReferenceData) -> Vec<(usize, usize)> {
+    let mut mentions: Vec<(usize, usize)> = RS_PATTERN.find_iter(citation)
+        .chain(SR_PATTERN.find_iter(citation))
+        .map(|m| (m.start(), m.end()))
+        .collect();
+
+    for word in WORD_PATTERN.find_iter(citation) {
+        let inside_rs = mentions.iter().any(|&(start, end)| word.start() >= start && word.start() < end);
+        if !inside_rs && has_law_mention(word.as_str(), reference) {
+            mentions.push((word.start(), word.end()));
+        }
+    }
+
+    mentions.sort_unstable();
+    mentions
+}
+
+/// One segment per law reference; a single-element vec when there is nothing to split
+pub fn segment_citation(citation: &str, reference: &ReferenceData) -> Vec<String> {
+    let markers: Vec<usize> = ARTICLE_START_PATTERN.find_iter(citation).map(|m| m.start()).collect();
+    let laws = law_mentions(citation, reference);
+    if markers.is_empty() || laws.len() < 2 {
+        return vec![citation.to_string()];
+    }
+
+    // A law mention opens a group unless articles since the previous mention
+    // are waiting for their law; those of an opening mention are its own
+    let mut opening = Vec::with_capacity(laws.len());
+    let mut previous_end = 0;
+    for (i, &(law_start, law_end)) in laws.iter().enumerate() {
+        let pending = markers.iter().any(|&marker| marker >= previous_end && marker < law_start);
+        let previous_opening = i > 0 && opening[i - 1];
+        opening.push(!pending || previous_opening);
+        previous_end = law_end;
+    }
+
+    let mut boundaries = Vec::new();
+    for (i, &(law_start, law_end)) in laws.iter().enumerate() {
+        if opening[i] {
+            // "BV Art. 8 und EMRK Art. 14": the mention starts the group
+            boundaries.push(law_start);
+        } else if let Some(marker) = markers.iter().copied().find(|&marker| marker >= law_end) {
+            // "Art. 8 BV und Art. 14 EMRK": the next article marker starts a
+            // group, unless an opening mention comes first ("Art. 8 BV und EMRK Art. 14")
+            let opened_before = laws.iter().zip(&opening)
+                .any(|(&(other_start, _), &other_opening)| other_opening && other_start >= law_end && other_start < marker);
+            if !opened_before {
+                boundaries.push(marker);
+            }
+        }
+    }
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    // A segment needs a law and an article of its own; otherwise it is merged
+    // with its neighbour ("Art. 8 BV und Art. 9", "BV Art. 8 und EMRK")
+    let is_complete = |start: usize, end: usize| {
+        laws.iter().any(|&(law_start, _)| law_start >= start && law_start < end)
+            && markers.iter().any(|&marker| marker >= start && marker < end)
+    };
+    let mut starts = vec![0];
+    for (i, &start) in boundaries.iter().enumerate() {
+        let end = boundaries.get(i + 1).copied().unwrap_or(citation.len());
+        if start > 0 && is_complete(start, end) {
+            starts.push(start);
+        }
+    }
+    while starts.len() > 1 && !is_complete(0, starts[1]) {
+        starts.remove(1);
+    }
+
+    // Neighbours citing the same law stay one citation ("Art. 41 CO en
+    // relation avec art. 97 CO"), they would only compare with each other
+    let law_of = |start: usize, end: usize| -> Vec<String> {
+        laws.iter()
+            .filter(|&&(law_start, _)| law_start >= start && law_start < end)
+            .map(|&(law_start, law_end)| {
+                let mention = citation[law_start..law_end].trim_end_matches('.');
+                normalize_to_rs_number(mention, reference, None).unwrap_or_else(|| mention.to_lowercase())
+            })
+            .collect()
+    };
+    let mut i = 1;
+    while i < starts.len() {
+        let end = starts.get(i + 1).copied().unwrap_or(citation.len());
+        if law_of(starts[i - 1], starts[i]) == law_of(starts[i], end) {
+            starts.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    if starts.len() == 1 {
+        return vec![citation.to_string()];
+    }
+
+    let mut ends: Vec<usize> = starts[1..].to_vec();
+    ends.push(citation.len());
+    starts.iter()
+        .zip(ends)
+        .map(|(&start, end)| trim_connectors(&citation[start..end]).trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_multi_law_citations() {
+        let reference = ReferenceData::default();
+        let cases: [(&str, &[&str]); 11] = [
+            ("Art. 8 BV und Art. 6 EMRK", &["Art. 8 BV", "Art. 6 EMRK"]),
+            ("Art. 8 BV i.V.m. Art. 14 EMRK", &["Art. 8 BV", "Art. 14 EMRK"]),
+            ("BV Art. 8 und EMRK Art. 14", &["BV Art. 8", "EMRK Art. 14"]),
+            ("BV Art. 8 lit. e EMRK Art. 6", &["BV Art. 8 lit. e", "EMRK Art. 6"]),
+            ("Art. 8 und 9 BV", &["Art. 8 und 9 BV"]),
+            ("Art. 8 BV und Art. 9", &["Art. 8 BV und Art. 9"]),
+            ("Art. 8 BV und EMRK Art. 14", &["Art. 8 BV", "EMRK Art. 14"]),
+            ("BV Art. 8 und Art. 14 EMRK", &["BV Art. 8 und Art. 14 EMRK"]),
+            ("Art. 8 BV Art. 14 EMRK", &["Art. 8 BV", "Art. 14 EMRK"]),
+            ("Art. 41 CO en relation avec art. 97 CO", &["Art. 41 CO en relation avec art. 97 CO"]),
+            ("Art. 8 BV, Art. 9 BV und Art. 6 EMRK", &["Art. 8 BV, Art. 9 BV", "Art. 6 EMRK"]),
+        ];
+        for (citation, expected) in cases {
+            assert_eq!(segment_citation(citation, &reference), expected, "{}", citation);
+        }
+    }
+
+    #[test]
+    fn trims_connectors() {
+        let cases = [
+            ("Art. 8 BV und", "Art. 8 BV"),
+            ("Art. 8 BV, e", "Art. 8 BV"),
+            ("Art. 8 Cost. e", "Art. 8 Cost."),
+            ("Art. 5 lit. e", "Art. 5 lit. e"),
+            ("art. 5 let. o", "art. 5 let. o"),
+        ];
+        for (segment, expected) in cases {
+            assert_eq!(trim_connectors(segment), expected, "{}", segment);
+        }
+    }
+}
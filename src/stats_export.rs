@@ -0,0 +1,137 @@
+// Machine-readable statistics written next to the match outputs
+//
+//   logs/stats_<config>.json           full AnalysisStats plus derived rates
+//   logs/law_stats_<config>.csv        one row per law group
+//   logs/comparison_<a>_vs_<b>.json    metric, value under A and B, delta
+//   logs/comparison_<a>_vs_<b>.csv     same rows as CSV
+use crate::unparseable::UnparseableReason;
+use crate::{AnalysisStats, ResolutionMethod};
+use serde::Serialize;
+use std::fs::File;
+
+#[derive(Serialize)]
+struct StatsExport<'a> {
+    #[serde(flatten)]
+    stats: &'a AnalysisStats,
+    parsing_rate: f64,
+    unparseable_rate: f64,
+    match_rate: f64,
+}
+
+#[derive(Serialize)]
+struct MetricRow {
+    metric: String,
+    a: f64,
+    b: f64,
+    delta: f64,
+}
+
+#[derive(Serialize)]
+struct ComparisonExport<'a> {
+    config_a: &'a str,
+    config_b: &'a str,
+    metrics: Vec<MetricRow>,
+}
+
+pub fn write_stats(stats: &AnalysisStats) -> Result<(), Box<dyn std::error::Error>> {
+    let json_path = format!("logs/stats_{}.json", stats.config_name);
+    let export = StatsExport {
+        stats,
+        parsing_rate: stats.parsing_rate(),
+        unparseable_rate: stats.unparseable_rate(),
+        match_rate: stats.match_rate(),
+    };
+    serde_json::to_writer_pretty(File::create(&json_path)?, &export)?;
+
+    let csv_path = format!("logs/law_stats_{}.csv", stats.config_name);
+    let mut writer = csv::Writer::from_path(&csv_path)?;
+
+    let mut header = vec![
+        "law".to_string(),
+        "federal".to_string(),
+        "citations".to_string(),
+        "comparisons".to_string(),
+        "same_article_matches".to_string(),
+        "match_rate".to_string(),
+    ];
+    header.extend(ResolutionMethod::ALL.iter().map(|method| method_column(*method)));
+    writer.write_record(&header)?;
+
+    for law in &stats.per_law {
+        let mut row = vec![
+            law.law.clone(),
+            law.federal.to_string(),
+            law.citations.to_string(),
+            law.comparisons.to_string(),
+            law.same_article_matches.to_string(),
+            format!("{:.4}", law.match_rate()),
+        ];
+        row.extend(ResolutionMethod::ALL.iter()
+            .map(|method| law.methods.get(method).copied().unwrap_or(0).to_string()));
+        writer.write_record(&row)?;
+    }
+    writer.flush()?;
+
+    println!("  ✓ Statistics written to {} and {}", json_path, csv_path);
+    Ok(())
+}
+
+pub fn write_comparison(a: &AnalysisStats, b: &AnalysisStats) -> Result<(), Box<dyn std::error::Error>> {
+    let mut metrics = Vec::new();
+    let mut push = |metric: &str, value_a: f64, value_b: f64| {
+        metrics.push(MetricRow {
+            metric: metric.to_string(),
+            a: value_a,
+            b: value_b,
+            delta: value_b - value_a,
+        });
+    };
+
+    push("total_citations", a.total_citations as f64, b.total_citations as f64);
+    push("parsed_citations", a.parsed_citations as f64, b.parsed_citations as f64);
+    push("parsing_rate", a.parsing_rate(), b.parsing_rate());
+    push("unparseable_citations", a.unparseable_citations as f64, b.unparseable_citations as f64);
+    push("unparseable_rate", a.unparseable_rate(), b.unparseable_rate());
+    push("unique_laws", a.unique_laws as f64, b.unique_laws as f64);
+    push("federal_laws", a.federal_laws as f64, b.federal_laws as f64);
+    push("cantonal_laws", a.cantonal_laws as f64, b.cantonal_laws as f64);
+    push("total_comparisons", a.total_comparisons as f64, b.total_comparisons as f64);
+    push("same_article_matches", a.same_article_matches as f64, b.same_article_matches as f64);
+    push("match_rate", a.match_rate(), b.match_rate());
+    push("former_versions", a.phase1.former_versions as f64, b.phase1.former_versions as f64);
+    push("renumbered", a.phase1.renumbered as f64, b.phase1.renumbered as f64);
+    push("unknown_articles", a.phase1.unknown_articles as f64, b.phase1.unknown_articles as f64);
+    push("repealed_articles", a.phase1.repealed_articles as f64, b.phase1.repealed_articles as f64);
+    for method in ResolutionMethod::ALL {
+        push(&method_column(method), a.method_count(method) as f64, b.method_count(method) as f64);
+    }
+    for reason in UnparseableReason::ALL {
+        push(&format!("unparseable_{}", reason.as_str()), a.reason_count(reason) as f64, b.reason_count(reason) as f64);
+    }
+    for reason in UnparseableReason::ALL {
+        push(&format!("dropped_{}", reason.as_str()), a.dropped_count(reason) as f64, b.dropped_count(reason) as f64);
+    }
+    push("dropped_digit_only", a.phase1.dropped_digit_only as f64, b.phase1.dropped_digit_only as f64);
+
+    let base_path = format!("logs/comparison_{}_vs_{}", a.config_name, b.config_name);
+    let export = ComparisonExport {
+        config_a: &a.config_name,
+        config_b: &b.config_name,
+        metrics,
+    };
+    serde_json::to_writer_pretty(File::create(format!("{}.json", base_path))?, &export)?;
+
+    let mut writer = csv::Writer::from_path(format!("{}.csv", base_path))?;
+    for row in &export.metrics {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+
+    println!("  ✓ Comparison written to {}.json and {}.csv", base_path, base_path);
+    Ok(())
+}
+
+/// Column name of a resolution method count, e.g. "method_title_match"
+fn method_column(method: ResolutionMethod) -> String {
+    format!("method_{}", method.as_str())
+}
@@ -0,0 +1,97 @@
+// Element-level pass attaching split-off fragments to the preceding citation
+//
+// LLM-extracted citation lists often split one reference over several entries,
+// e.g. ["Art. 29 BV", "Abs. 2"]. A fragment inherits law and articles from the
+// nearest preceding resolved citation of the same element.
+use crate::unparseable::UnparseableReason;
+use crate::versions::group_key;
+use crate::{AHashMap, AHashSet, CitationInfo, ResolutionMethod, UnparseableCitation};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+pub fn stitch_fragments(
+    unparseable_list: Vec<UnparseableCitation>,
+    law_groups: &mut AHashMap<String, Vec<CitationInfo>>,
+    output_suffix: &str,
+) -> Vec<UnparseableCitation> {
+    println!("\n🧵 Phase 1.2: Stitching fragments to preceding citations...");
+
+    std::fs::create_dir_all("logs").ok();
+    let stitched_path = format!("logs/stitched_fragments_{}.txt", output_suffix);
+    let stitched_file = File::create(&stitched_path)
+        .unwrap_or_else(|e| panic!("Failed to create {}: {}", stitched_path, e));
+    let mut stitched_writer = BufWriter::new(stitched_file);
+
+    let fragment_elements: AHashSet<String> = unparseable_list.iter()
+        .filter(|u| u.reason == UnparseableReason::Fragment)
+        .map(|u| u.element_id.clone())
+        .collect();
+
+    // Resolved citations of the affected elements in text order; the segments
+    // of a split citation share its position
+    let mut resolved_by_element: AHashMap<String, Vec<CitationInfo>> = AHashMap::new();
+    for citation in law_groups.values().flatten() {
+        if fragment_elements.contains(&citation.element_id) {
+            resolved_by_element.entry(citation.element_id.clone()).or_default().push(citation.clone());
+        }
+    }
+    for citations in resolved_by_element.values_mut() {
+        citations.sort_by_key(|c| (c.position, c.segment));
+    }
+
+    let mut candidates: Vec<UnparseableCitation> = Vec::new();
+    let mut still_unparseable = Vec::new();
+    for unparseable in unparseable_list {
+        if unparseable.reason == UnparseableReason::Fragment {
+            candidates.push(unparseable);
+        } else {
+            still_unparseable.push(unparseable);
+        }
+    }
+    // In position order, so "lit. a" after a stitched "Abs. 2" finds it as parent
+    candidates.sort_by(|a, b| a.element_id.cmp(&b.element_id).then(a.position.cmp(&b.position)));
+
+    let mut stitched = 0;
+    for fragment in candidates {
+        let parent = resolved_by_element.get(&fragment.element_id)
+            .and_then(|citations| citations.iter().rev().find(|c| c.position < fragment.position))
+            .cloned();
+
+        let Some(parent) = parent else {
+            still_unparseable.push(fragment);
+            continue;
+        };
+
+        writeln!(stitched_writer, "{} | {} {} | {}", fragment.citation, parent.citation, fragment.citation, parent.law)
+            .ok();
+
+        let citation = CitationInfo {
+            element_id: fragment.element_id.clone(),
+            position: fragment.position,
+            segment: 0,
+            citation: format!("{} {}", parent.citation, fragment.citation),
+            law: parent.law.clone(),
+            version: parent.version,
+            articles: parent.articles.clone(),
+            renumbered: parent.renumbered,
+            resolution_method: ResolutionMethod::Stitched,
+            confidence: parent.confidence * ResolutionMethod::Stitched.base_confidence(),
+        };
+
+        let siblings = resolved_by_element.entry(fragment.element_id.clone()).or_default();
+        let insert_at = siblings.partition_point(|c| c.position < citation.position);
+        siblings.insert(insert_at, citation.clone());
+
+        law_groups.entry(group_key(&parent.law, parent.version)).or_default().push(citation);
+        stitched += 1;
+    }
+
+    stitched_writer.flush().ok();
+    println!("  ✓ Stitched {} fragments", stitched);
+    if stitched > 0 {
+        println!("  ✓ Saved {} stitched fragments to {}", stitched, stitched_path);
+    }
+    println!("  ✓ Still unparseable: {}", still_unparseable.len());
+
+    still_unparseable
+}
@@ -0,0 +1,139 @@
+// `mine-stopwords` subcommand: propose words that are not law abbreviations
+//
+//   cartesian-law-analysis mine-stopwords [--input <dataset>] [--min-count <n>]
+//
+// Runs Phase 1 with the default configuration and collects the words that end
+// up as CANTONAL_ fallback keys. A word used as a fallback key at least
+// --min-count times that never stands in a law-like position anywhere in the
+// corpus (right after an article marker and number, possibly with paragraphs
+// and letters, as in "Art. 12 BauG" or "Art. 12 Abs. 2 BauG", or right before
+// an article marker as in "BauG Art. 12") is proposed as a stopword in
+// logs/proposed_stopwords.txt, with its count and an example citation. After
+// review, the lines to keep go into learned_stopwords.txt, which the parser
+// loads alongside its built-in common words.
+use crate::config::ParserConfig;
+use crate::law_structure::LawStructure;
+use crate::overrides::Overrides;
+use crate::{
+    arg_value, group_citations_by_law, load_and_filter_dataset, load_reference, markers,
+    ResolutionMethod, DEFAULT_INPUT_PATH,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+const PROPOSALS_PATH: &str = "logs/proposed_stopwords.txt";
+
+/// Fallback uses needed before a word is proposed, when --min-count is not given
+const DEFAULT_MIN_COUNT: usize = 3;
+
+/// Text before a word ending in an article marker and number, with optional
+/// subdivisions or "ff."-style suffix: "Art. 12 a", "Art. 41 ff.", "art. 8 al. 2 let. b".
+/// A bare number ("Abs. 2 Mai", "Ziff. 3 Gemeinde") does not count.
+static AFTER_ARTICLE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"\b{}\s*\d+(?:\s?(?:[a-z]|bis|ter|quater)\b)?(?:\s*(?:{}\.?\s*(?:\d+|[a-z]\b)|{}\.?))*\s*[.,]?\s*$",
+        *markers::ARTICLE, *markers::SUBDIVISION, *markers::FOLLOWING
+    )).unwrap()
+});
+
+/// Text after a word starting with an article marker and number: " Art. 12"
+static BEFORE_ARTICLE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"^\s*{}\s*\d", *markers::ARTICLE)).unwrap()
+});
+
+/// Capitalised words as in the abbreviation extraction, also behind a former-version
+/// "a" prefix ("aBauR")
+static WORD_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|[^\p{L}\d])a?([A-ZÄÖÜ][A-ZÄÖÜa-zäöü]{1,15})\b").unwrap()
+});
+
+/// A year right after the word: "14 Mai 1990" is a date, not "Art. 14 Mai"
+static YEAR_AFTER_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*\d{4}\b").unwrap());
+
+#[derive(Debug, Default)]
+struct WordStats {
+    fallback_citations: usize,
+    law_like_positions: usize,
+    example: String,
+}
+
+/// Whether the word at `start..end` of `citation` stands where a law abbreviation would
+fn is_law_like_position(citation: &str, start: usize, end: usize) -> bool {
+    let (before, after) = (&citation[..start], &citation[end..]);
+    (AFTER_ARTICLE_PATTERN.is_match(before) && !YEAR_AFTER_PATTERN.is_match(after))
+        || BEFORE_ARTICLE_PATTERN.is_match(after)
+}
+
+/// Entry point of `mine-stopwords`; `args` follow the subcommand name
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let input_file = arg_value(args, "--input").unwrap_or_else(|| DEFAULT_INPUT_PATH.to_string());
+    let min_count = match arg_value(args, "--min-count") {
+        Some(value) => value.parse::<usize>().map_err(|e| format!("--min-count {}: {}", value, e))?,
+        None => DEFAULT_MIN_COUNT,
+    };
+
+    let reference = load_reference(args)?;
+    let elements = load_and_filter_dataset(&input_file)?;
+    let (law_groups, _, _) = group_citations_by_law(
+        &elements,
+        &reference,
+        &Overrides::default(),
+        &LawStructure::default(),
+        &ParserConfig::default(),
+    );
+
+    println!("\n⛏️  Mining stopwords from CANTONAL_ fallback keys...");
+    let mut words: BTreeMap<String, WordStats> = BTreeMap::new();
+    for citation in law_groups.values().flatten() {
+        if citation.resolution_method != ResolutionMethod::CantonalFallback {
+            continue;
+        }
+        let Some(key) = citation.law.strip_prefix("CANTONAL_") else { continue };
+        // "CANTONAL_BAUR@former" counts for "baur"
+        let word = key.split('@').next().unwrap_or(key);
+        let stats = words.entry(word.to_lowercase()).or_default();
+        stats.fallback_citations += 1;
+        if stats.example.is_empty() {
+            stats.example = citation.citation.clone();
+        }
+    }
+
+    // Law-like positions anywhere in the corpus, not only in the fallback citations
+    for raw_citation in elements.iter().flat_map(|element| &element.articles_de_loi) {
+        for caps in WORD_PATTERN.captures_iter(raw_citation) {
+            let word = caps.get(1).expect("group 1 always participates");
+            if let Some(stats) = words.get_mut(&word.as_str().to_lowercase()) {
+                if is_law_like_position(raw_citation, word.start(), word.end()) {
+                    stats.law_like_positions += 1;
+                }
+            }
+        }
+    }
+
+    let mut proposals: Vec<(&String, &WordStats)> = words.iter()
+        .filter(|(_, stats)| stats.fallback_citations >= min_count && stats.law_like_positions == 0)
+        .collect();
+    proposals.sort_by(|a, b| b.1.fallback_citations.cmp(&a.1.fallback_citations).then(a.0.cmp(b.0)));
+
+    std::fs::create_dir_all("logs")?;
+    let mut writer = BufWriter::new(File::create(PROPOSALS_PATH)?);
+    writeln!(writer, "# Stopwords proposed by mine-stopwords from {} ({} elements)", input_file, elements.len())?;
+    writeln!(writer, "# CANTONAL_ fallback keys used at least {} times, never after an article", min_count)?;
+    writeln!(writer, "# marker and number or before an article marker. Review, then copy the lines to keep")?;
+    writeln!(writer, "# into learned_stopwords.txt.")?;
+    for (word, stats) in &proposals {
+        writeln!(writer, "{:<24} # {} fallback citations, e.g. {:?}", word, stats.fallback_citations, stats.example)?;
+    }
+    writer.flush()?;
+
+    println!("  ✓ Fallback keys seen: {}", words.len());
+    println!("  ✓ Proposed stopwords: {}", proposals.len());
+    for (word, stats) in proposals.iter().take(10) {
+        println!("    - {:<20} {:>6} citations", word, stats.fallback_citations);
+    }
+    println!("  ✓ Saved to {}", PROPOSALS_PATH);
+    Ok(())
+}
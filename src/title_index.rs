@@ -0,0 +1,259 @@
+// Inverted index over the law titles of the reference data, scored with BM25
+//
+// Built once when the reference data is loaded. A query only touches the postings of
+// its own words instead of scanning every title, and returns ranked candidates
+// (one per RS number) with their score and the share of query words found.
+//
+// Titles and queries go through the same tokenizer: folded, stemmed in the
+// title's language, and German compounds additionally indexed by their parts,
+// so "Bundesgerichtsgesetz" and "Bundesgesetz über das Bundesgericht" meet.
+//
+// The enactment date of a title is indexed as one more (rare, so heavily
+// weighted) term. A query with a date favours the act of that date and
+// demotes similarly named acts of another date.
+use crate::tokenizer::{detect_language, fold_diacritics, split_compound, stem, stem_variants, tokenize, Language, TitleDate};
+use crate::{AHashMap, AHashSet};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Titles shorter than this are only used for exact matches
+const MIN_FUZZY_TITLE_CHARS: usize = 20;
+
+/// Score factor of a title dated differently from the query
+const DATE_MISMATCH_FACTOR: f64 = 0.5;
+
+// BM25 parameters (usual defaults)
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+#[derive(Debug)]
+struct IndexedTitle {
+    rs_number: String,
+    /// Number of tokens
+    length: usize,
+    date: Option<TitleDate>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TitleCandidate {
+    pub rs_number: String,
+    pub score: f64,
+    /// Distinct query words found in the title
+    pub matched_words: usize,
+    /// The title has the query's date
+    pub date_matched: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct TitleIndex {
+    /// Folded full title -> RS number
+    exact: AHashMap<String, String>,
+    titles: Vec<IndexedTitle>,
+    /// Stem -> (title id, term frequency)
+    postings: AHashMap<String, Vec<(u32, u32)>>,
+    /// Stems of German titles, the parts compounds are split into
+    compound_parts: AHashSet<String>,
+    average_length: f64,
+}
+
+/// Index terms of a title word: its stem, plus the parts of a German compound
+fn title_terms(word: &str, language: Option<Language>, compound_parts: &AHashSet<String>) -> Vec<String> {
+    let Some(language) = language else { return vec![word.to_string()] };
+    let mut terms = vec![stem(word, language)];
+    if language == Language::German {
+        if let Some(parts) = split_compound(word, compound_parts) {
+            terms.extend(parts);
+        }
+    }
+    terms.sort_unstable();
+    terms.dedup();
+    terms
+}
+
+impl TitleIndex {
+    pub fn build(title_to_rs: &HashMap<String, String>) -> Self {
+        let start = Instant::now();
+        let mut index = TitleIndex::default();
+
+        // Sorted so title ids (and ties between equal scores) are deterministic
+        let mut entries: Vec<(&String, &String)> = title_to_rs.iter().collect();
+        entries.sort();
+
+        let mut fuzzy_titles: Vec<(&String, &String, Vec<String>, Option<Language>)> = Vec::new();
+        for (title, rs_number) in entries {
+            // Same title in another case or spelling: indexed once
+            let folded = fold_diacritics(title);
+            if index.exact.contains_key(&folded) {
+                continue;
+            }
+            index.exact.insert(folded, rs_number.clone());
+            if title.chars().count() < MIN_FUZZY_TITLE_CHARS {
+                continue;
+            }
+            let tokens = tokenize(title);
+            let language = detect_language(&tokens);
+            if language == Some(Language::German) {
+                index.compound_parts.extend(tokens.iter()
+                    .filter(|token| token.chars().count() >= 4)
+                    .map(|token| stem(token, Language::German)));
+            }
+            fuzzy_titles.push((title, rs_number, tokens, language));
+        }
+
+        let mut total_length = 0;
+        for (title, rs_number, tokens, language) in fuzzy_titles {
+            let id = index.titles.len() as u32;
+            let mut frequencies: AHashMap<String, u32> = AHashMap::new();
+            for token in &tokens {
+                for term in title_terms(token, language, &index.compound_parts) {
+                    *frequencies.entry(term).or_default() += 1;
+                }
+            }
+            let date = TitleDate::parse(title);
+            if let Some(date) = date {
+                frequencies.insert(date.term(), 1);
+            }
+            for (token, frequency) in frequencies {
+                index.postings.entry(token).or_default().push((id, frequency));
+            }
+
+            total_length += tokens.len();
+            index.titles.push(IndexedTitle {
+                rs_number: rs_number.clone(),
+                length: tokens.len(),
+                date,
+            });
+        }
+        index.average_length = total_length as f64 / index.titles.len().max(1) as f64;
+        println!("  ✓ Indexed {} law titles ({} distinct words) in {} ms",
+                 index.exact.len(),
+                 index.postings.len(),
+                 start.elapsed().as_millis());
+        index
+    }
+
+    pub fn title_count(&self) -> usize {
+        self.exact.len()
+    }
+
+    /// RS number of a title written exactly as in the mapping, ignoring case and accents
+    pub fn exact_match(&self, title: &str) -> Option<&str> {
+        self.exact.get(&fold_diacritics(title)).map(String::as_str)
+    }
+
+    /// BM25 score of one term per title containing it
+    fn term_scores(&self, term: &str) -> AHashMap<u32, f64> {
+        let Some(postings) = self.postings.get(term) else { return AHashMap::new() };
+        let title_count = self.titles.len() as f64;
+        let document_frequency = postings.len() as f64;
+        let idf = (1.0 + (title_count - document_frequency + 0.5) / (document_frequency + 0.5)).ln();
+
+        postings.iter()
+            .map(|&(id, frequency)| {
+                let length = self.titles[id as usize].length as f64;
+                let frequency = frequency as f64;
+                let score = idf * frequency * (K1 + 1.0)
+                    / (frequency + K1 * (1.0 - B + B * length / self.average_length));
+                (id, score)
+            })
+            .collect()
+    }
+
+    /// Score of one query word per title: its best stem, or else the mean of its
+    /// compound parts when the title has all of them
+    fn word_scores(&self, word: &str, language: Option<Language>) -> AHashMap<u32, f64> {
+        let variants = match language {
+            Some(language) => vec![stem(word, language)],
+            None => {
+                let mut variants = stem_variants(word);
+                variants.push(word.to_string());
+                variants
+            }
+        };
+
+        let mut scores: AHashMap<u32, f64> = AHashMap::new();
+        for variant in variants {
+            for (id, score) in self.term_scores(&variant) {
+                let entry = scores.entry(id).or_default();
+                *entry = entry.max(score);
+            }
+        }
+
+        if matches!(language, None | Some(Language::German)) {
+            if let Some(parts) = split_compound(word, &self.compound_parts) {
+                let part_scores: Vec<AHashMap<u32, f64>> = parts.iter().map(|part| self.term_scores(part)).collect();
+                for (&id, &first) in &part_scores[0] {
+                    let found: Option<Vec<f64>> = part_scores[1..].iter()
+                        .map(|scores| scores.get(&id).copied())
+                        .collect();
+                    let Some(rest) = found else { continue };
+                    let mean = (first + rest.iter().sum::<f64>()) / parts.len() as f64;
+                    let entry = scores.entry(id).or_default();
+                    *entry = entry.max(mean);
+                }
+            }
+        }
+        scores
+    }
+
+    /// Best titles for `words` (folded, as from `tokenize`), highest BM25 score
+    /// first, at most one per RS number. `language` selects the stemmer; when
+    /// unknown every language's stem is tried. With a `date`, titles of that
+    /// date score its term and titles of another date are demoted.
+    pub fn search(
+        &self,
+        words: &[String],
+        date: Option<TitleDate>,
+        language: Option<Language>,
+        limit: usize,
+    ) -> Vec<TitleCandidate> {
+        let mut query: Vec<&str> = words.iter().map(String::as_str).collect();
+        query.sort_unstable();
+        query.dedup();
+
+        let mut scores: AHashMap<u32, (f64, usize)> = AHashMap::new();
+        for word in query {
+            for (id, score) in self.word_scores(word, language) {
+                let entry = scores.entry(id).or_default();
+                entry.0 += score;
+                entry.1 += 1;
+            }
+        }
+
+        let mut date_matches: AHashSet<u32> = AHashSet::new();
+        if let Some(date) = date {
+            for (id, score) in self.term_scores(&date.term()) {
+                scores.entry(id).or_default().0 += score;
+                date_matches.insert(id);
+            }
+            for (id, (score, _)) in scores.iter_mut() {
+                if self.titles[*id as usize].date.is_some_and(|title_date| title_date != date) {
+                    *score *= DATE_MISMATCH_FACTOR;
+                }
+            }
+        }
+
+        let mut ranked: Vec<(u32, f64, usize)> = scores.into_iter()
+            .map(|(id, (score, matched))| (id, score, matched))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut candidates: Vec<TitleCandidate> = Vec::new();
+        for (id, score, matched_words) in ranked {
+            let rs_number = &self.titles[id as usize].rs_number;
+            if candidates.iter().any(|candidate| &candidate.rs_number == rs_number) {
+                continue;
+            }
+            candidates.push(TitleCandidate {
+                rs_number: rs_number.clone(),
+                score,
+                matched_words,
+                date_matched: date_matches.contains(&id),
+            });
+            if candidates.len() == limit {
+                break;
+            }
+        }
+        candidates
+    }
+}
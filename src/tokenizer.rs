@@ -0,0 +1,255 @@
+// Title tokenizer: diacritic folding, word splitting, light stemming for
+// German, French and Italian, and German compound splitting
+//
+// Titles and citations are folded the same way ("Règlement" -> "reglement",
+// "Übergangsbestimmungen" -> "ubergangsbestimmungen"), so matches no longer
+// depend on accents or on one word being a substring of another.
+//
+// Enactment dates ("du 20 décembre 1968", "vom 20. Dezember 1968",
+// "del 20 dicembre 1968") are parsed from titles and citations alike.
+use crate::AHashSet;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    German,
+    French,
+    Italian,
+}
+
+impl Language {
+    const ALL: [Language; 3] = [Language::German, Language::French, Language::Italian];
+
+    /// Frequent words of law titles, folded
+    fn stopwords(self) -> &'static [&'static str] {
+        match self {
+            Language::German => &["der", "die", "das", "des", "den", "dem", "und", "uber", "vom", "zur", "zum", "fur"],
+            Language::French => &["le", "la", "les", "du", "des", "de", "et", "sur", "au", "aux", "relative", "concernant"],
+            Language::Italian => &["il", "lo", "la", "i", "gli", "le", "della", "del", "dei", "delle", "e", "sulla", "sul", "concernente"],
+        }
+    }
+}
+
+/// Month names per language, folded, January first
+const MONTHS: [[&str; 12]; 3] = [
+    ["januar", "februar", "marz", "april", "mai", "juni", "juli", "august", "september", "oktober", "november", "dezember"],
+    ["janvier", "fevrier", "mars", "avril", "mai", "juin", "juillet", "aout", "septembre", "octobre", "novembre", "decembre"],
+    ["gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre"],
+];
+
+/// "20 decembre 1968", "1er avril 1999", "20. dezember 1968", "1° aprile 1999" (folded text)
+static DATE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    let months: Vec<&str> = MONTHS.iter().flatten().copied().collect();
+    Regex::new(&format!(r"\b(\d{{1,2}})(?:er|\.|°)?\s*({})\s+(\d{{4}})\b", months.join("|"))).unwrap()
+});
+
+/// Calendar date of an act, as written in its title
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TitleDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl TitleDate {
+    /// First date written out in `text`: "loi du 20 décembre 1968" -> 1968-12-20
+    pub fn parse(text: &str) -> Option<Self> {
+        let folded = fold_diacritics(text);
+        DATE_PATTERN.captures_iter(&folded).find_map(|caps| {
+            let day: u8 = caps[1].parse().ok()?;
+            let month = MONTHS.iter()
+                .find_map(|names| names.iter().position(|name| *name == &caps[2]))? as u8 + 1;
+            let year: u16 = caps[3].parse().ok()?;
+            (1..=31).contains(&day).then_some(TitleDate { year, month, day })
+        })
+    }
+
+    /// Index term of the date; cannot collide with a word
+    pub fn term(self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Month name in any language (folded)
+pub fn is_month(word: &str) -> bool {
+    MONTHS.iter().flatten().any(|name| *name == word)
+}
+
+/// Lowercase without diacritics: "Règlement" -> "reglement", "Straße" -> "strasse".
+/// Combining marks of decomposed input are dropped as well.
+pub fn fold_diacritics(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => folded.push('a'),
+            'ç' => folded.push('c'),
+            'è' | 'é' | 'ê' | 'ë' => folded.push('e'),
+            'ì' | 'í' | 'î' | 'ï' => folded.push('i'),
+            'ñ' => folded.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' => folded.push('o'),
+            'ù' | 'ú' | 'û' | 'ü' => folded.push('u'),
+            'ý' | 'ÿ' => folded.push('y'),
+            'ß' => folded.push_str("ss"),
+            'æ' => folded.push_str("ae"),
+            'œ' => folded.push_str("oe"),
+            '\u{2019}' | '\u{2018}' => folded.push('\''),
+            '\u{0300}'..='\u{036F}' => {}
+            _ => folded.push(c),
+        }
+    }
+    folded
+}
+
+/// Folded words, split on anything that is not a letter or digit
+/// ("l'administration" -> ["l", "administration"])
+pub fn tokenize(text: &str) -> Vec<String> {
+    fold_diacritics(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Language with the most stopwords among `words`, if any
+pub fn detect_language(words: &[String]) -> Option<Language> {
+    Language::ALL.iter()
+        .map(|&language| {
+            let hits = words.iter().filter(|word| language.stopwords().contains(&word.as_str())).count();
+            (language, hits)
+        })
+        .filter(|&(_, hits)| hits > 0)
+        .max_by_key(|&(_, hits)| hits)
+        .map(|(language, _)| language)
+}
+
+fn strip_suffix_keeping(word: &str, suffix: &str, min_stem: usize) -> Option<String> {
+    let stem = word.strip_suffix(suffix)?;
+    (stem.chars().count() >= min_stem).then(|| stem.to_string())
+}
+
+/// Light inflectional stemming: plural and case endings only, so different
+/// words are not conflated ("gesetzes" -> "gesetz", "droits" -> "droit",
+/// "federali" -> "federal")
+pub fn stem(word: &str, language: Language) -> String {
+    match language {
+        Language::German => ["ern", "em", "en", "er", "es", "e", "n", "s"].iter()
+            .find_map(|suffix| strip_suffix_keeping(word, suffix, 4))
+            .unwrap_or_else(|| word.to_string()),
+        Language::French => {
+            let singular = strip_suffix_keeping(word, "aux", 3).map(|stem| stem + "al")
+                .or_else(|| strip_suffix_keeping(word, "s", 4))
+                .or_else(|| strip_suffix_keeping(word, "x", 4))
+                .unwrap_or_else(|| word.to_string());
+            strip_suffix_keeping(&singular, "e", 4).unwrap_or(singular)
+        }
+        Language::Italian => ["a", "e", "i", "o"].iter()
+            .find_map(|suffix| strip_suffix_keeping(word, suffix, 4))
+            .unwrap_or_else(|| word.to_string()),
+    }
+}
+
+/// Stems of a word in every language, for queries whose language is unknown
+pub fn stem_variants(word: &str) -> Vec<String> {
+    let mut variants: Vec<String> = Language::ALL.iter().map(|&language| stem(word, language)).collect();
+    variants.sort_unstable();
+    variants.dedup();
+    variants
+}
+
+/// German compound split into known stems, allowing the linking "s"/"es" between
+/// parts: "bundesgerichtsgesetz" -> ["bundesgericht", "gesetz"]. `None` unless
+/// the word splits into at least two parts of `vocabulary`.
+pub fn split_compound(word: &str, vocabulary: &AHashSet<String>) -> Option<Vec<String>> {
+    const MIN_PART: usize = 4;
+
+    let chars: Vec<char> = word.chars().collect();
+    let n = chars.len();
+    if n < 2 * MIN_PART {
+        return None;
+    }
+
+    let known_part = |start: usize, end: usize| -> Option<String> {
+        let part: String = chars[start..end].iter().collect();
+        let candidates = [
+            Some(part.clone()),
+            part.strip_suffix("es").map(str::to_string),
+            part.strip_suffix('s').map(str::to_string),
+        ];
+        candidates.into_iter()
+            .flatten()
+            .filter(|candidate| candidate.chars().count() >= MIN_PART)
+            .map(|candidate| stem(&candidate, Language::German))
+            .find(|stemmed| vocabulary.contains(stemmed))
+    };
+
+    // best[i]: fewest parts covering chars[..i]
+    let mut best: Vec<Option<Vec<String>>> = vec![None; n + 1];
+    best[0] = Some(Vec::new());
+    for end in MIN_PART..=n {
+        for start in 0..=end - MIN_PART {
+            let Some(prefix) = &best[start] else { continue };
+            // The whole word is always "known"; only genuine splits count
+            if start == 0 && end == n {
+                continue;
+            }
+            if let Some(part) = known_part(start, end) {
+                if best[end].as_ref().is_none_or(|parts| parts.len() > prefix.len() + 1) {
+                    let mut parts = prefix.clone();
+                    parts.push(part);
+                    best[end] = Some(parts);
+                }
+            }
+        }
+    }
+
+    best[n].take().filter(|parts| parts.len() >= 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stems_inflections() {
+        let cases = [
+            ("gesetzes", Language::German, "gesetz"),
+            ("bestimmungen", Language::German, "bestimmung"),
+            ("kinder", Language::German, "kind"),
+            ("droits", Language::French, "droit"),
+            ("federaux", Language::French, "federal"),
+            ("loi", Language::French, "loi"),
+            ("federali", Language::Italian, "federal"),
+            ("legge", Language::Italian, "legg"),
+        ];
+        for (word, language, expected) in cases {
+            assert_eq!(stem(word, language), expected, "{} ({:?})", word, language);
+        }
+        assert_eq!(stem_variants("droits"), ["droit", "droits"]);
+    }
+
+    #[test]
+    fn splits_compounds() {
+        let vocabulary: AHashSet<String> = ["bundesgericht", "gesetz", "verfahren"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        let cases: [(&str, Option<&[&str]>); 3] = [
+            ("bundesgerichtsgesetz", Some(&["bundesgericht", "gesetz"])),
+            ("verfahrensgesetz", Some(&["verfahren", "gesetz"])),
+            ("gesetz", None),
+        ];
+        for (word, expected) in cases {
+            let expected = expected.map(|parts| parts.iter().map(|part| part.to_string()).collect::<Vec<_>>());
+            assert_eq!(split_compound(word, &vocabulary), expected, "{}", word);
+        }
+    }
+
+    #[test]
+    fn tokenizes_folded_words() {
+        assert_eq!(tokenize("l'Administration fédérale"), ["l", "administration", "federale"]);
+        assert_eq!(fold_diacritics("Straße"), "strasse");
+        assert!(is_month("decembre"));
+        assert!(!is_month("domaine"));
+    }
+}
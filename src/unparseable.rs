@@ -0,0 +1,226 @@
+// Categorisation of citations that could not be resolved to a law
+use crate::tokenizer::{is_month, tokenize};
+use crate::{format_number, markers, UnparseableCitation};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+static FRAGMENT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"^{}\.\s", *markers::SUBDIVISION)).unwrap()
+});
+
+static ARTICLE_MARKER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"\b{}\s*\d", *markers::ARTICLE)).unwrap()
+});
+
+static PAGE_REFERENCE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"^\d+\s*(?:f\b|{})\.?$", *markers::FOLLOWING)).unwrap()
+});
+
+// All caps (StGB is caught by the mixed pattern) or mixed case with 2+ capitals: "OR", "VwVG", "LPP2"
+static ABBREV_SHAPE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[A-ZÄÖÜ][a-zäöü]*[A-ZÄÖÜ][A-Za-zÄÖÜäöü0-9-]*").unwrap()
+});
+
+const REPEALED_MARKERS: [&str; 3] = ["abrogé", "aufgehoben", "abrogato"];
+
+const INCOMPLETE_ENDINGS: [&str; 8] = ["de la", "du", "des", "de l'", "della", "del", "ist (", "sind ("];
+
+const PROPOSAL_WORDS: [&str; 5] = ["proposition", "motion", "postulat", "initiative", "anfrage"];
+
+const LOCAL_REGULATION_WORDS: [&str; 6] = [
+    "reglement", "règlement", "statut", "ordonnance communale",
+    "feuerwehrreglement", "öffentlichkeitsgesetz",
+];
+
+/// Why a citation could not be resolved, most specific category first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnparseableReason {
+    /// "abrogé", "aufgehoben", "abrogato"
+    GarbageRepealed,
+    /// Bare "Abs. 2", "al. 1", "cpv. 2", "let. b" without article or law
+    Fragment,
+    /// A date rather than a citation
+    Date,
+    /// "207ff.", "218 f", "45 segg."
+    PageReference,
+    /// Cut-off sentence ending in "de la", "du", "des", ...
+    Incomplete,
+    /// Proposal, motion, postulate or initiative
+    Proposal,
+    /// "Art. 128" with no law at all
+    ArticleOnly,
+    /// Communal or cantonal regulation named in full
+    NonSwissLaw,
+    /// Contains an abbreviation-shaped token that could not be resolved
+    UnknownAbbreviation,
+    /// Neither an article number nor a recognisable law
+    NoArticle,
+    Unknown,
+}
+
+impl UnparseableReason {
+    pub const ALL: [UnparseableReason; 11] = [
+        UnparseableReason::GarbageRepealed,
+        UnparseableReason::Fragment,
+        UnparseableReason::Date,
+        UnparseableReason::PageReference,
+        UnparseableReason::Incomplete,
+        UnparseableReason::Proposal,
+        UnparseableReason::ArticleOnly,
+        UnparseableReason::NonSwissLaw,
+        UnparseableReason::UnknownAbbreviation,
+        UnparseableReason::NoArticle,
+        UnparseableReason::Unknown,
+    ];
+
+    /// Same name as in the JSON outputs
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnparseableReason::GarbageRepealed => "garbage-repealed",
+            UnparseableReason::Fragment => "fragment",
+            UnparseableReason::Date => "date",
+            UnparseableReason::PageReference => "page-reference",
+            UnparseableReason::Incomplete => "incomplete",
+            UnparseableReason::Proposal => "proposal",
+            UnparseableReason::ArticleOnly => "article-only",
+            UnparseableReason::NonSwissLaw => "non-swiss-law",
+            UnparseableReason::UnknownAbbreviation => "unknown-abbreviation",
+            UnparseableReason::NoArticle => "no-article",
+            UnparseableReason::Unknown => "unknown",
+        }
+    }
+
+    /// Not a citation at all; no amount of context will resolve it
+    pub fn is_garbage(self) -> bool {
+        matches!(
+            self,
+            UnparseableReason::GarbageRepealed
+                | UnparseableReason::Date
+                | UnparseableReason::PageReference
+                | UnparseableReason::Incomplete
+                | UnparseableReason::Proposal
+        )
+    }
+}
+
+pub fn classify_unparseable(citation: &str) -> UnparseableReason {
+    let trimmed = citation.trim();
+    let citation_lower = trimmed.to_lowercase();
+    let has_article = ARTICLE_MARKER_PATTERN.is_match(trimmed);
+
+    if REPEALED_MARKERS.iter().any(|marker| citation_lower.contains(marker)) {
+        return UnparseableReason::GarbageRepealed;
+    }
+
+    if FRAGMENT_PATTERN.is_match(trimmed) {
+        return UnparseableReason::Fragment;
+    }
+
+    // Law titles like "loi du 14 décembre 1990" with an article are valid
+    if !has_article && tokenize(trimmed).iter().any(|word| is_month(word)) {
+        return UnparseableReason::Date;
+    }
+
+    if PAGE_REFERENCE_PATTERN.is_match(trimmed) {
+        return UnparseableReason::PageReference;
+    }
+
+    if INCOMPLETE_ENDINGS.iter().any(|ending| trimmed.ends_with(ending)) {
+        return UnparseableReason::Incomplete;
+    }
+
+    if PROPOSAL_WORDS.iter().any(|word| citation_lower.contains(word)) {
+        return UnparseableReason::Proposal;
+    }
+
+    if citation_lower.starts_with("art") && trimmed.chars().count() < 30 && !ABBREV_SHAPE_PATTERN.is_match(trimmed) {
+        return UnparseableReason::ArticleOnly;
+    }
+
+    if LOCAL_REGULATION_WORDS.iter().any(|word| citation_lower.contains(word)) {
+        return UnparseableReason::NonSwissLaw;
+    }
+
+    if ABBREV_SHAPE_PATTERN.is_match(trimmed) {
+        return UnparseableReason::UnknownAbbreviation;
+    }
+
+    if !trimmed.chars().any(|c| c.is_ascii_digit()) {
+        return UnparseableReason::NoArticle;
+    }
+
+    UnparseableReason::Unknown
+}
+
+pub fn count_unparseable_reasons(unparseable: &[UnparseableCitation]) -> BTreeMap<UnparseableReason, usize> {
+    let mut counts = BTreeMap::new();
+    for citation in unparseable {
+        *counts.entry(citation.reason).or_default() += 1;
+    }
+    counts
+}
+
+/// Per-category counts with a few examples each
+pub fn print_unparseable_summary(unparseable: &[UnparseableCitation]) {
+    if unparseable.is_empty() {
+        return;
+    }
+
+    let mut examples: BTreeMap<UnparseableReason, Vec<&str>> = BTreeMap::new();
+    for citation in unparseable {
+        let entry = examples.entry(citation.reason).or_default();
+        if entry.len() < 3 {
+            entry.push(&citation.citation);
+        }
+    }
+
+    let counts = count_unparseable_reasons(unparseable);
+    let garbage: usize = counts.iter()
+        .filter(|(reason, _)| reason.is_garbage())
+        .map(|(_, count)| count)
+        .sum();
+
+    println!("\n🧹 Unparseable citations by category:");
+    for (reason, count) in &counts {
+        println!("  {} {:<21} {:>8} ({:.1}%)",
+                 if reason.is_garbage() { "✗" } else { "•" },
+                 reason.as_str(),
+                 format_number(*count),
+                 100.0 * *count as f64 / unparseable.len() as f64);
+        for example in &examples[reason] {
+            println!("      e.g. {}", example);
+        }
+    }
+    println!("  ✓ Garbage (not real citations): {} ({:.1}%)",
+             format_number(garbage),
+             100.0 * garbage as f64 / unparseable.len() as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_unparseable_citations() {
+        let cases = [
+            ("Art. 12 abrogé", UnparseableReason::GarbageRepealed),
+            ("Abs. 2", UnparseableReason::Fragment),
+            ("14 mars 2019", UnparseableReason::Date),
+            ("207ff.", UnparseableReason::PageReference),
+            ("loi sur l'aménagement de la", UnparseableReason::Incomplete),
+            ("Postulat 12.3456", UnparseableReason::Proposal),
+            ("Art. 128", UnparseableReason::ArticleOnly),
+            ("Art. 4 Feuerwehrreglement der Gemeinde Muri", UnparseableReason::NonSwissLaw),
+            ("Art. 12 XYZG", UnparseableReason::UnknownAbbreviation),
+            ("loi sur les forêts", UnparseableReason::NoArticle),
+            ("§ 12 der Verordnung", UnparseableReason::Unknown),
+        ];
+        assert_eq!(cases.len(), UnparseableReason::ALL.len());
+        for (citation, expected) in cases {
+            assert_eq!(classify_unparseable(citation), expected, "{}", citation);
+        }
+    }
+}
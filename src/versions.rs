@@ -0,0 +1,156 @@
+// Current vs. former version of a cited law
+//
+// "43 aCP", "aBauR" or "aArt. 12 OR" cite the law as it stood before a revision.
+// Without such a marker, a decision dated before the current version of the law
+// came into force (`in_force_since` in the law structure export) also cites the
+// former version. Former versions get their own law group ("311.0@former"), so
+// Art. 43 of the old and the new code are never compared with each other.
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+// "aCP", "aBauR", "aArt. 12"
+static GLUED_A_PREFIX_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|[\s(])a([A-ZÄÖÜ][A-Za-zÄÖÜäöüß]*[A-ZÄÖÜ]|Art\.)").unwrap()
+});
+
+// "43 a CP" once normalisation split the prefix. Only a marker when the source
+// had "aCP": "Art. 29 a BV" is article 29a and "lit. a BV" a letter
+static SPLIT_A_PREFIX_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|[\s(])a\s+([A-ZÄÖÜ][A-Za-zÄÖÜäöüß]*[A-ZÄÖÜ]|Art\.)").unwrap()
+});
+
+static FORMER_WORD_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:alt|alte[ns]?|ancien(?:ne)?|previgente|vecchio|vecchia)\b|\banc\.").unwrap()
+});
+
+// "2019-03-14" or "14.03.2019"
+static ISO_DATE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4})-(\d{2})-(\d{2})").unwrap()
+});
+
+static SWISS_DATE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{1,2})\.(\d{1,2})\.(\d{4})$").unwrap()
+});
+
+const FORMER_SUFFIX: &str = "@former";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LawVersion {
+    #[default]
+    Current,
+    /// Version in force before the latest revision ("alt", "a" prefix)
+    Former,
+}
+
+/// Abbreviations carrying a glued "a" prefix in the citation as written,
+/// before normalisation splits "aCP" into "a CP": ["CP"]
+pub fn glued_prefixes(raw_citation: &str) -> Vec<&str> {
+    GLUED_A_PREFIX_PATTERN.captures_iter(raw_citation)
+        .map(|caps| caps.get(1).unwrap().as_str())
+        .collect()
+}
+
+/// Explicit former-version marker in the (possibly normalised) citation text;
+/// `glued` are the prefixed abbreviations of the source, see `glued_prefixes`
+pub fn has_former_marker(citation: &str, glued: &[&str]) -> bool {
+    GLUED_A_PREFIX_PATTERN.is_match(citation)
+        || SPLIT_A_PREFIX_PATTERN.captures_iter(citation).any(|caps| glued.contains(&&caps[1]))
+        || FORMER_WORD_PATTERN.is_match(citation)
+}
+
+/// Version cited, from the citation marker or else the decision date
+pub fn detect_version(
+    citation: &str,
+    glued: &[&str],
+    decision_date: Option<&str>,
+    in_force_since: Option<&str>,
+) -> LawVersion {
+    if has_former_marker(citation, glued) {
+        return LawVersion::Former;
+    }
+    match (decision_date, in_force_since) {
+        // ISO dates compare correctly as strings
+        (Some(decided), Some(since)) if decided < since => LawVersion::Former,
+        _ => LawVersion::Current,
+    }
+}
+
+/// Law key naming a former version itself, like the alias "OG" -> "173.110@former"
+/// for the act the current "173.110" (BGG) replaced
+pub fn is_former_key(law: &str) -> bool {
+    law.ends_with(FORMER_SUFFIX)
+}
+
+/// Law key without the former-version suffix
+pub fn base_law(law: &str) -> &str {
+    law.strip_suffix(FORMER_SUFFIX).unwrap_or(law)
+}
+
+/// Key of the law group a citation belongs to
+pub fn group_key(law: &str, version: LawVersion) -> String {
+    match version {
+        LawVersion::Former if !is_former_key(law) => format!("{}{}", law, FORMER_SUFFIX),
+        _ => law.to_string(),
+    }
+}
+
+/// ISO form ("2019-03-14") of a decision date written as ISO or "14.03.2019";
+/// `None` for a month outside 1-12 or a day outside 1-31
+pub fn parse_decision_date(date: &str) -> Option<String> {
+    let date = date.trim();
+    let (year, month, day) = match ISO_DATE_PATTERN.captures(date) {
+        Some(caps) => (caps[1].to_string(), caps[2].parse::<u32>().ok()?, caps[3].parse::<u32>().ok()?),
+        None => {
+            let caps = SWISS_DATE_PATTERN.captures(date)?;
+            (caps[3].to_string(), caps[2].parse().ok()?, caps[1].parse().ok()?)
+        }
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(format!("{}-{:02}-{:02}", year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(raw_citation: &str, normalized: &str) -> LawVersion {
+        detect_version(normalized, &glued_prefixes(raw_citation), None, None)
+    }
+
+    #[test]
+    fn detects_former_markers() {
+        let cases = [
+            ("Art. 43 aCP", "Art. 43 a CP", LawVersion::Former),
+            ("Art. 12 lit. b aBauR", "Art. 12 lit. b a BauR", LawVersion::Former),
+            ("aArt. 12 OR", "aArt. 12 OR", LawVersion::Former),
+            ("Art. 4 alt OR", "Art. 4 alt OR", LawVersion::Former),
+            ("Art. 29 a BV", "Art. 29 a BV", LawVersion::Current),
+            ("Art. 5 lit. a BV", "Art. 5 lit. a BV", LawVersion::Current),
+            ("Art. 29 a BV und Art. 5 aCP", "Art. 29 a BV", LawVersion::Current),
+        ];
+        for (raw_citation, normalized, expected) in cases {
+            assert_eq!(version(raw_citation, normalized), expected, "{}", normalized);
+        }
+        assert_eq!(detect_version("Art. 8 BV", &[], Some("1998-05-04"), Some("2000-01-01")), LawVersion::Former);
+    }
+
+    #[test]
+    fn parses_decision_dates() {
+        let cases = [
+            ("2019-03-14", Some("2019-03-14")),
+            ("2019-03-14T00:00:00", Some("2019-03-14")),
+            ("4.3.2019", Some("2019-03-04")),
+            ("2020-13-45", None),
+            ("2020-00-10", None),
+            ("32.01.2020", None),
+            ("14 March 2019", None),
+        ];
+        for (date, expected) in cases {
+            assert_eq!(parse_decision_date(date).as_deref(), expected, "{}", date);
+        }
+    }
+}